@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use json_parser::JsonDocument;
+
+// An array of small records, large enough that copying it is measurable
+fn records(count: usize) -> String {
+    let items: Vec<String> = (0..count)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "name": "item {}", "tags": ["a", "b"], "active": true}}"#,
+                i, i
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(",\n"))
+}
+
+// Compare validation alone against parsing, which also copies the content
+fn bench_validate_vs_parse(c: &mut Criterion) {
+    let input = records(2_000);
+
+    let mut group = c.benchmark_group("validate_vs_parse");
+    group.bench_function("validate", |b| {
+        b.iter(|| JsonDocument::validate(black_box(&input)))
+    });
+    group.bench_function("parse", |b| {
+        b.iter(|| JsonDocument::parse(black_box(&input)))
+    });
+    group.finish();
+}
+
+// Compare validating bytes directly against decoding them first and validating the `&str`
+fn bench_validate_bytes(c: &mut Criterion) {
+    let input = records(2_000).into_bytes();
+
+    let mut group = c.benchmark_group("validate_bytes_vs_str");
+    group.bench_function("validate_bytes", |b| {
+        b.iter(|| JsonDocument::validate_bytes(black_box(&input)))
+    });
+    group.bench_function("from_utf8_then_validate", |b| {
+        b.iter(|| JsonDocument::validate(std::str::from_utf8(black_box(&input)).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_validate_vs_parse, bench_validate_bytes);
+criterion_main!(benches);
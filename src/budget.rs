@@ -0,0 +1,95 @@
+//! # Resource Budgets
+//!
+//! This module provides the limits enforced by `JsonDocument::parse_within_budget`.
+
+use pest::iterators::Pair;
+
+use crate::{JsonParseError, Rule};
+
+/// Resource limits for parsing untrusted input.
+///
+/// Depth counts nested containers the way `JsonValue::depth` does, nodes count
+/// every value including containers (but not object keys), and string lengths
+/// are measured in bytes of source text between the quotes, covering keys too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Maximum container nesting depth
+    pub max_depth: usize,
+    /// Maximum number of values in the document
+    pub max_nodes: usize,
+    /// Maximum length of the input in bytes, including surrounding whitespace
+    pub max_bytes: usize,
+    /// Maximum length of any string or key
+    pub max_string_len: usize,
+}
+
+impl Default for Budget {
+    /// Limits generous enough for typical API payloads.
+    fn default() -> Self {
+        Budget {
+            max_depth: 128,
+            max_nodes: 1_000_000,
+            max_bytes: 16 * 1024 * 1024,
+            max_string_len: 1024 * 1024,
+        }
+    }
+}
+
+impl Budget {
+    /// Checks the input length before any parsing is done.
+    pub(crate) fn check_bytes(&self, input: &str) -> Result<(), JsonParseError> {
+        if input.len() > self.max_bytes {
+            return Err(exceeded("max_bytes", self.max_bytes));
+        }
+        Ok(())
+    }
+
+    /// Walks the parsed tree in document order, failing on the first limit exceeded.
+    pub(crate) fn check_tree(&self, root: Pair<Rule>) -> Result<(), JsonParseError> {
+        let mut nodes = 0;
+        self.visit(root, 0, &mut nodes)
+    }
+
+    fn visit(
+        &self,
+        pair: Pair<Rule>,
+        depth: usize,
+        nodes: &mut usize,
+    ) -> Result<(), JsonParseError> {
+        if pair.as_rule() == Rule::pair {
+            let mut inner = pair.into_inner();
+            if let Some(key) = inner.next() {
+                self.check_string(&key)?;
+            }
+            return inner.try_for_each(|value| self.visit(value, depth, nodes));
+        }
+
+        *nodes += 1;
+        if *nodes > self.max_nodes {
+            return Err(exceeded("max_nodes", self.max_nodes));
+        }
+
+        match pair.as_rule() {
+            Rule::string => self.check_string(&pair),
+            Rule::object | Rule::array => {
+                if depth + 1 > self.max_depth {
+                    return Err(exceeded("max_depth", self.max_depth));
+                }
+                pair.into_inner()
+                    .try_for_each(|child| self.visit(child, depth + 1, nodes))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_string(&self, pair: &Pair<Rule>) -> Result<(), JsonParseError> {
+        if pair.as_str().len() - 2 > self.max_string_len {
+            return Err(exceeded("max_string_len", self.max_string_len));
+        }
+        Ok(())
+    }
+}
+
+fn exceeded(limit: &'static str, max: usize) -> JsonParseError {
+    JsonParseError::BudgetExceeded { limit, max }
+}
@@ -0,0 +1,168 @@
+//! # Value Builders
+//!
+//! This module provides builders and conversions for constructing `JsonValue`
+//! trees in code.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{JsonNumber, JsonValue};
+
+/// Builds a `JsonValue::Object`, keeping members in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectBuilder {
+    members: Vec<(String, JsonValue)>,
+}
+
+/// Builds a `JsonValue::Array`.
+#[derive(Debug, Clone, Default)]
+pub struct ArrayBuilder {
+    items: Vec<JsonValue>,
+}
+
+/// Converts a Rust value into a `JsonValue` for the builders.
+///
+/// Implemented for everything that is `Into<JsonValue>`, and also for `&str`
+/// and `String`, which become `JsonValue::String`. Strings have no `From`
+/// conversion because `JsonValue::try_from` parses them as JSON text instead.
+pub trait IntoJsonValue {
+    /// Performs the conversion.
+    fn into_json_value(self) -> JsonValue;
+}
+
+impl JsonValue {
+    /// Starts building an object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_parser::JsonValue;
+    ///
+    /// let user = JsonValue::object()
+    ///     .insert("name", "Ada")
+    ///     .insert("age", 36)
+    ///     .insert("email", None::<&str>)
+    ///     .insert("roles", JsonValue::array().push("admin").push(true))
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     user.to_string(),
+    ///     r#"{"name":"Ada","age":36,"email":null,"roles":["admin",true]}"#
+    /// );
+    /// ```
+    pub fn object() -> ObjectBuilder {
+        ObjectBuilder::default()
+    }
+
+    /// Starts building an array.
+    pub fn array() -> ArrayBuilder {
+        ArrayBuilder::default()
+    }
+}
+
+impl ObjectBuilder {
+    /// Appends a member; a repeated key is kept as a second member, as in
+    /// parsed objects.
+    pub fn insert<K: Into<String>, V: IntoJsonValue>(mut self, key: K, value: V) -> Self {
+        self.members.push((key.into(), value.into_json_value()));
+        self
+    }
+
+    /// Finishes the object.
+    pub fn build(self) -> JsonValue {
+        JsonValue::Object(self.members)
+    }
+}
+
+impl ArrayBuilder {
+    /// Appends an element.
+    pub fn push<V: IntoJsonValue>(mut self, value: V) -> Self {
+        self.items.push(value.into_json_value());
+        self
+    }
+
+    /// Finishes the array.
+    pub fn build(self) -> JsonValue {
+        JsonValue::Array(self.items)
+    }
+}
+
+impl From<ObjectBuilder> for JsonValue {
+    fn from(builder: ObjectBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<ArrayBuilder> for JsonValue {
+    fn from(builder: ArrayBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(b: bool) -> Self {
+        JsonValue::Bool(b)
+    }
+}
+
+impl From<i64> for JsonValue {
+    fn from(n: i64) -> Self {
+        JsonValue::Number(JsonNumber::I64(n))
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(n: f64) -> Self {
+        JsonValue::Number(JsonNumber::F64(n))
+    }
+}
+
+/// Converts `None` to `Null` and `Some` through the inner value's conversion.
+impl<T: IntoJsonValue> From<Option<T>> for JsonValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(JsonValue::Null, IntoJsonValue::into_json_value)
+    }
+}
+
+impl<T: Into<JsonValue>> IntoJsonValue for T {
+    fn into_json_value(self) -> JsonValue {
+        self.into()
+    }
+}
+
+impl IntoJsonValue for &str {
+    fn into_json_value(self) -> JsonValue {
+        JsonValue::String(self.to_string())
+    }
+}
+
+impl IntoJsonValue for String {
+    fn into_json_value(self) -> JsonValue {
+        JsonValue::String(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonDocument;
+
+    #[test]
+    fn test_builders_match_parsed() {
+        let built = JsonValue::object()
+            .insert("id", 7)
+            .insert(String::from("ratio"), 0.5)
+            .insert("tags", JsonValue::array().push("a").push(None::<bool>))
+            .insert("nested", JsonValue::object().insert("ok", Some(false)))
+            .build();
+        let parsed = JsonDocument::parse(
+            r#"{"id": 7, "ratio": 0.5, "tags": ["a", null], "nested": {"ok": false}}"#,
+        )
+        .unwrap()
+        .to_value()
+        .unwrap();
+
+        assert_eq!(built, parsed);
+        assert_eq!(JsonValue::array().build().to_string(), "[]");
+    }
+}
@@ -0,0 +1,498 @@
+//! # Syntax Diagnostics
+//!
+//! This module turns raw pest failures into friendlier messages for the most
+//! common JSON mistakes.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use pest::error::{ErrorVariant, InputLocation};
+use pest::{Parser, Position};
+
+use crate::{JsonParseError, JsonParser, JsonRootType, Rule};
+
+/// Converts a pest failure into a `JsonParseError`.
+///
+/// pest reports where its last rule attempt failed, which is often far from
+/// the actual mistake, so the input is rescanned token by token looking for a
+/// trailing comma, a single-quoted string, an unquoted key, a missing comma,
+/// or a missing colon. When one of these is found the result is a `Syntax`
+/// error pointing at it. Its `expected` list describes the rules pest tried
+/// when pest failed at that same spot, and otherwise the tokens the scanner
+/// would have accepted there. An illegal escape inside a string yields
+/// `InvalidEscape` instead, unless one of those mistakes comes first. Input
+/// that simply stops inside an object or array yields `UnclosedContainer`.
+/// Otherwise the pest error is kept as is.
+pub(crate) fn diagnose(input: &str, error: pest::error::Error<Rule>) -> JsonParseError {
+    let line_col = |pos| {
+        Position::new(input, pos)
+            .map(|pos| pos.line_col())
+            .unwrap_or((1, 1))
+    };
+    let mistake = find_mistake(input);
+    match (find_invalid_escape(input), mistake) {
+        (Some((pos, escape)), mistake)
+            if mistake.as_ref().is_none_or(|(first, ..)| pos < *first) =>
+        {
+            let (line, column) = line_col(pos);
+            JsonParseError::InvalidEscape {
+                escape,
+                line,
+                column,
+            }
+        }
+        (_, Some((pos, message, scanned))) => {
+            let (line, column) = line_col(pos);
+            JsonParseError::Syntax {
+                message: message.to_string(),
+                line,
+                column,
+                expected: expected_by_pest(&error, pos).unwrap_or(scanned),
+            }
+        }
+        (_, None) => match find_unclosed(input) {
+            Some(open) if is_truncated(input, &open) => {
+                let (opened_at, bracket) = open[open.len() - 1];
+                let (line, column) = line_col(opened_at);
+                JsonParseError::UnclosedContainer {
+                    kind: if bracket == '{' {
+                        JsonRootType::Object
+                    } else {
+                        JsonRootType::Array
+                    },
+                    opened_at,
+                    line,
+                    column,
+                }
+            }
+            _ => error.into(),
+        },
+    }
+}
+
+/// Finds the first backslash inside a double-quoted string that does not
+/// start a valid escape, returning its byte offset and the character after it.
+///
+/// A backslash that ends the input is reported with `\\` as its escape.
+fn find_invalid_escape(input: &str) -> Option<(usize, char)> {
+    let mut chars = input.char_indices();
+    let mut in_string = false;
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '"' => in_string = !in_string,
+            '\\' if in_string => match chars.next() {
+                Some((_, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')) => {}
+                Some((_, escape)) => return Some((pos, escape)),
+                None => return Some((pos, '\\')),
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds the brackets still open at the end of the input with their offsets,
+/// outermost first.
+///
+/// Returns `None` if every bracket is closed, a closing bracket does not
+/// match, or the input ends inside a string.
+fn find_unclosed(input: &str) -> Option<Vec<(usize, char)>> {
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (pos, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '{' | '[' => stack.push((pos, c)),
+            '}' | ']' if stack.pop()?.1 != opening(c) => return None,
+            _ => {}
+        }
+    }
+    Some(stack).filter(|stack| !stack.is_empty() && !in_string)
+}
+
+/// Whether the input is only cut short: after closing the `open` brackets it
+/// either parses or fails no earlier than where the input ended, as with a
+/// trailing comma.
+fn is_truncated(input: &str, open: &[(usize, char)]) -> bool {
+    let mut closed = String::from(input);
+    closed.extend(open.iter().rev().map(|&(_, bracket)| closing(bracket)));
+    match JsonParser::parse(Rule::lenient_json, &closed) {
+        Ok(_) => true,
+        Err(error) => match error.location {
+            InputLocation::Pos(pos) | InputLocation::Span((pos, _)) => pos >= input.len(),
+        },
+    }
+}
+
+/// Describes the rules pest expected, if it failed at byte offset `pos` and
+/// reported any.
+fn expected_by_pest(error: &pest::error::Error<Rule>, pos: usize) -> Option<Vec<String>> {
+    let failed_at = match error.location {
+        InputLocation::Pos(at) | InputLocation::Span((at, _)) => at,
+    };
+    match &error.variant {
+        ErrorVariant::ParsingError { positives, .. }
+            if failed_at == pos && !positives.is_empty() =>
+        {
+            Some(describe_positives(positives))
+        }
+        _ => None,
+    }
+}
+
+/// Describes the rules pest expected at its failure point, collapsing the
+/// full set of value rules into "a JSON value".
+pub(crate) fn describe_positives(positives: &[Rule]) -> Vec<String> {
+    const VALUES: [Rule; 6] = [
+        Rule::object,
+        Rule::array,
+        Rule::string,
+        Rule::number,
+        Rule::boolean,
+        Rule::null,
+    ];
+    if VALUES.iter().all(|rule| positives.contains(rule)) {
+        let mut expected = vec!["a JSON value".to_string()];
+        expected.extend(
+            positives
+                .iter()
+                .filter(|rule| !VALUES.contains(rule))
+                .map(|&rule| describe_rule(rule)),
+        );
+        return expected;
+    }
+    positives.iter().map(|&rule| describe_rule(rule)).collect()
+}
+
+fn describe_rule(rule: Rule) -> String {
+    match rule {
+        Rule::json | Rule::document => "a JSON value",
+        Rule::object => "an object",
+        Rule::array => "an array",
+        Rule::pair | Rule::string => "a string",
+        Rule::number | Rule::js_number => "a number",
+        Rule::boolean => "a boolean",
+        Rule::null => "null",
+        Rule::EOI => "end of input",
+        rule => return format!("{:?}", rule),
+    }
+    .to_string()
+}
+
+/// What the scanner expects at the next token.
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    /// A value, at the root or after `:`
+    Value,
+    /// A value or `]`, right after `[`
+    ValueOrClose,
+    /// A value after `,` in an array
+    NextElement,
+    /// A key or `}`, right after `{`
+    KeyOrClose,
+    /// A key after `,` in an object
+    NextKey,
+    /// The `:` after a key
+    Colon,
+    /// A `,` or the closing bracket after a value
+    CommaOrClose,
+}
+
+impl Expect {
+    /// Lists the tokens accepted in this state, with `open` the bracket of
+    /// the innermost open container.
+    fn tokens(self, open: Option<char>) -> Vec<String> {
+        let tokens: &[&str] = match (self, open) {
+            (Expect::Value | Expect::NextElement, _) => &["a JSON value"],
+            (Expect::ValueOrClose, _) => &["a JSON value", "]"],
+            (Expect::KeyOrClose, _) => &["a string", "}"],
+            (Expect::NextKey, _) => &["a string"],
+            (Expect::Colon, _) => &[":"],
+            (Expect::CommaOrClose, None) => &["end of input"],
+            (Expect::CommaOrClose, Some('{')) => &[",", "}"],
+            (Expect::CommaOrClose, Some(_)) => &[",", "]"],
+        };
+        tokens.iter().map(|token| token.to_string()).collect()
+    }
+}
+
+/// A lexical token recognized by the scanner.
+enum Token {
+    Punct(char),
+    SingleQuote,
+    String,
+    /// A literal or number, such as `true` or `-1.5`
+    Literal,
+    /// Any other run of characters, such as an unquoted key
+    Word,
+}
+
+/// Scans for the first recognizable mistake, returning its byte offset, a
+/// message, and the tokens that were expected there.
+fn find_mistake(input: &str) -> Option<(usize, &'static str, Vec<String>)> {
+    let mut stack = Vec::new();
+    let mut expect = Expect::Value;
+    let mut rest = input;
+
+    loop {
+        let trimmed = rest.trim_start();
+        let pos = input.len() - trimmed.len();
+        let (token, len) = next_token(trimmed)?;
+        rest = &trimmed[len..];
+        let in_object = stack.last() == Some(&'{');
+        let open = stack.last().copied();
+        let found = move |message| Some((pos, message, expect.tokens(open)));
+
+        expect = match (expect, token) {
+            (_, Token::SingleQuote) if expect != Expect::Colon => {
+                return found("single-quoted string; JSON strings use double quotes");
+            }
+            (Expect::Value | Expect::ValueOrClose | Expect::NextElement, token) => match token {
+                Token::String | Token::Literal => Expect::CommaOrClose,
+                Token::Punct(c @ ('{' | '[')) => {
+                    stack.push(c);
+                    if c == '{' {
+                        Expect::KeyOrClose
+                    } else {
+                        Expect::ValueOrClose
+                    }
+                }
+                Token::Punct(']') if expect == Expect::ValueOrClose => {
+                    stack.pop();
+                    Expect::CommaOrClose
+                }
+                Token::Punct(']') if expect == Expect::NextElement => {
+                    return found("trailing comma before closing bracket");
+                }
+                _ => return None,
+            },
+            (Expect::KeyOrClose | Expect::NextKey, token) => match token {
+                Token::String => Expect::Colon,
+                Token::Punct('}') if expect == Expect::KeyOrClose => {
+                    stack.pop();
+                    Expect::CommaOrClose
+                }
+                Token::Punct('}') => return found("trailing comma before closing brace"),
+                Token::Word | Token::Literal => {
+                    return found("unquoted object key; keys must be double-quoted strings");
+                }
+                _ => return None,
+            },
+            (Expect::Colon, Token::Punct(':')) => Expect::Value,
+            (Expect::Colon, _) => return found("missing colon after object key"),
+            (Expect::CommaOrClose, _) if stack.is_empty() => return None,
+            (Expect::CommaOrClose, token) => match token {
+                Token::Punct(',') if in_object => Expect::NextKey,
+                Token::Punct(',') => Expect::NextElement,
+                Token::Punct(c @ ('}' | ']')) if stack.last() == Some(&opening(c)) => {
+                    stack.pop();
+                    Expect::CommaOrClose
+                }
+                Token::String | Token::Literal | Token::Punct('{' | '[') if in_object => {
+                    return found("missing comma between object members");
+                }
+                Token::String | Token::Literal | Token::Punct('{' | '[') => {
+                    return found("missing comma between array elements");
+                }
+                _ => return None,
+            },
+        };
+    }
+}
+
+/// Reads the token at the start of `input`, returning it with its byte length.
+fn next_token(input: &str) -> Option<(Token, usize)> {
+    let first = input.chars().next()?;
+    match first {
+        '{' | '}' | '[' | ']' | ',' | ':' => Some((Token::Punct(first), 1)),
+        '\'' => Some((Token::SingleQuote, 1)),
+        '"' => {
+            let mut escaped = false;
+            for (index, c) in input.char_indices().skip(1) {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => return Some((Token::String, index + 1)),
+                    _ => {}
+                }
+            }
+            // An unterminated string is left for pest to report
+            None
+        }
+        _ => {
+            let len = input
+                .find(|c: char| c.is_whitespace() || "{}[],:\"'".contains(c))
+                .unwrap_or(input.len());
+            let word = &input[..len];
+            let literal = matches!(word, "true" | "false" | "null")
+                || word.starts_with(|c: char| c == '-' || c.is_ascii_digit());
+            Some((if literal { Token::Literal } else { Token::Word }, len))
+        }
+    }
+}
+
+fn closing(opening: char) -> char {
+    if opening == '{' {
+        '}'
+    } else {
+        ']'
+    }
+}
+
+fn opening(closing: char) -> char {
+    if closing == '}' {
+        '{'
+    } else {
+        '['
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use crate::{JsonDocument, JsonParseError, JsonRootType};
+
+    fn syntax_message(input: &str) -> (String, usize, usize) {
+        match JsonDocument::parse(input) {
+            Err(JsonParseError::Syntax {
+                message,
+                line,
+                column,
+                ..
+            }) => (message, line, column),
+            other => panic!("expected a syntax error for {}, got {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn test_unclosed_container() {
+        let unclosed = |input: &str| match JsonDocument::parse(input) {
+            Err(JsonParseError::UnclosedContainer {
+                kind,
+                opened_at,
+                line,
+                column,
+            }) => (kind, opened_at, line, column),
+            other => panic!(
+                "expected an unclosed container for {}, got {:?}",
+                input, other
+            ),
+        };
+
+        assert_eq!(unclosed(r#"{"a": [1, 2"#), (JsonRootType::Array, 6, 1, 7));
+        assert_eq!(
+            unclosed("[1, {\"b\": [], \"c\": \"]\"\n"),
+            (JsonRootType::Object, 4, 1, 5)
+        );
+        assert_eq!(
+            unclosed("{\n  \"a\": {\"b\": 1}"),
+            (JsonRootType::Object, 0, 1, 1)
+        );
+        assert_eq!(unclosed("[1, 2,"), (JsonRootType::Array, 0, 1, 1));
+
+        for input in [r#"{"a": "x"#, "[1, 2}", "[1 2", "[1, 2]]"] {
+            assert!(
+                !matches!(
+                    JsonDocument::parse(input),
+                    Err(JsonParseError::UnclosedContainer { .. })
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_trailing_comma() {
+        let (message, line, column) = syntax_message(r#"{"key": "value",}"#);
+        assert_eq!(message, "trailing comma before closing brace");
+        assert_eq!((line, column), (1, 17));
+
+        let (message, _, _) = syntax_message("[1, 2, ]");
+        assert_eq!(message, "trailing comma before closing bracket");
+    }
+
+    #[test]
+    fn test_single_quoted_string() {
+        let (message, _, column) = syntax_message("{\"key\": 'value'}");
+        assert_eq!(
+            message,
+            "single-quoted string; JSON strings use double quotes"
+        );
+        assert_eq!(column, 9);
+    }
+
+    #[test]
+    fn test_unquoted_key() {
+        let (message, _, _) = syntax_message(r#"{"a": 1, b: 2}"#);
+        assert_eq!(
+            message,
+            "unquoted object key; keys must be double-quoted strings"
+        );
+    }
+
+    #[test]
+    fn test_missing_comma() {
+        let (message, _, column) = syntax_message("[1 2]");
+        assert_eq!(message, "missing comma between array elements");
+        assert_eq!(column, 4);
+
+        let (message, line, _) = syntax_message("{\n  \"a\": \"x\"\n  \"b\": 2\n}");
+        assert_eq!(message, "missing comma between object members");
+        assert_eq!(line, 3);
+    }
+
+    #[test]
+    fn test_expected_tokens() {
+        let expected = |input: &str| JsonDocument::parse(input).unwrap_err().expected();
+
+        assert_eq!(expected(r#"{"a": 1 "b": 2}"#), [",", "}"]);
+        assert_eq!(expected("[[1] 2]"), [",", "]"]);
+        assert_eq!(expected(r#"{"a" 1}"#), [":"]);
+        assert_eq!(expected("[1, ]"), ["a JSON value"]);
+        assert_eq!(expected(r#"{"a": 1,}"#), ["a string"]);
+        assert_eq!(expected(r#"{"key": }"#), ["a JSON value"]);
+        assert_eq!(expected("[1] x"), ["end of input"]);
+    }
+
+    #[test]
+    fn test_invalid_escape() {
+        let escape = |input: &str| match JsonDocument::parse(input) {
+            Err(JsonParseError::InvalidEscape {
+                escape,
+                line,
+                column,
+            }) => (escape, line, column),
+            other => panic!("expected an invalid escape for {}, got {:?}", input, other),
+        };
+
+        assert_eq!(escape(r#"["ok\n", "a\x"]"#), ('x', 1, 12));
+        assert_eq!(escape("{\n  \"k\\q\": 1\n}"), ('q', 2, 5));
+        assert_eq!(escape(r#"["a\"#), ('\\', 1, 4));
+        assert!(matches!(
+            JsonDocument::parse(r#"[1,] "\x""#),
+            Err(JsonParseError::Syntax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_colon() {
+        let (message, _, column) = syntax_message(r#"{"a" 1}"#);
+        assert_eq!(message, "missing colon after object key");
+        assert_eq!(column, 6);
+    }
+
+    #[test]
+    fn test_unrecognized_falls_back_to_pest() {
+        assert!(matches!(
+            JsonDocument::parse(r#"{"key": }"#),
+            Err(JsonParseError::PestError { .. })
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! # Structural Diff
+//!
+//! This module compares two `JsonValue` trees and reports where they differ
+//! as JSON Pointers.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::value::escape_pointer_token;
+use crate::JsonValue;
+
+/// One difference between two values, located by JSON Pointer (RFC 6901).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// A member or element present only in the second value
+    Added(String),
+    /// A member or element present only in the first value
+    Removed(String),
+    /// A value present in both but unequal, including a change of type
+    Changed(String),
+}
+
+impl JsonValue {
+    /// Lists the differences from this value to `other`, in document order.
+    ///
+    /// Objects are compared member by member regardless of order, arrays
+    /// element by element, and scalars with `==`, so `1` and `1.0` are equal.
+    pub fn diff(&self, other: &JsonValue) -> Vec<Difference> {
+        self.diff_ignoring(other, &[])
+    }
+
+    /// Lists the differences like `diff`, treating object members whose key
+    /// is in `ignore_keys` as equal at any depth, whatever their values and
+    /// even if only one side has them.
+    pub fn diff_ignoring(&self, other: &JsonValue, ignore_keys: &[&str]) -> Vec<Difference> {
+        let mut out = Vec::new();
+        diff_into(self, other, String::new(), ignore_keys, &mut out);
+        out
+    }
+}
+
+fn diff_into(
+    left: &JsonValue,
+    right: &JsonValue,
+    path: String,
+    ignore_keys: &[&str],
+    out: &mut Vec<Difference>,
+) {
+    match (left, right) {
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                let child = format!("{}/{}", path, index);
+                match (a.get(index), b.get(index)) {
+                    (Some(x), Some(y)) => diff_into(x, y, child, ignore_keys, out),
+                    (Some(_), None) => out.push(Difference::Removed(child)),
+                    (None, _) => out.push(Difference::Added(child)),
+                }
+            }
+        }
+        (JsonValue::Object(_) | JsonValue::Map(_), JsonValue::Object(_) | JsonValue::Map(_)) => {
+            let mut seen = BTreeSet::new();
+            let keys = left.entries().chain(right.entries()).map(|(key, _)| key);
+            for key in keys {
+                if ignore_keys.contains(&key) || !seen.insert(key) {
+                    continue;
+                }
+                let child = format!("{}/{}", path, escape_pointer_token(key));
+                match (left.get(key), right.get(key)) {
+                    (Some(x), Some(y)) => diff_into(x, y, child, ignore_keys, out),
+                    (Some(_), None) => out.push(Difference::Removed(child)),
+                    (None, _) => out.push(Difference::Added(child)),
+                }
+            }
+        }
+        _ if left != right => out.push(Difference::Changed(path)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonDocument;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let a = value(r#"{"a": 1, "b": [1, 2, 3], "c": {"d": "x"}, "e/f": true}"#);
+        let b = value(r#"{"c": {"d": "y"}, "a": 1.0, "b": [1, 5], "g": null}"#);
+
+        assert_eq!(
+            a.diff(&b),
+            [
+                Difference::Changed("/b/1".into()),
+                Difference::Removed("/b/2".into()),
+                Difference::Changed("/c/d".into()),
+                Difference::Removed("/e~1f".into()),
+                Difference::Added("/g".into()),
+            ]
+        );
+        assert!(a.diff(&a).is_empty());
+        assert_eq!(a.diff(&JsonValue::Null), [Difference::Changed("".into())]);
+    }
+
+    #[test]
+    fn test_diff_ignoring() {
+        let first = value(
+            r#"{"timestamp": 1700000000, "data": [{"id": 1, "timestamp": "10:00"}], "status": "ok"}"#,
+        );
+        let second = value(
+            r#"{"status": "ok", "data": [{"timestamp": "10:05", "id": 1}], "timestamp": 1700000042}"#,
+        );
+
+        assert_eq!(first.diff(&second).len(), 2);
+        assert!(first.diff_ignoring(&second, &["timestamp"]).is_empty());
+        assert_eq!(
+            first.diff_ignoring(&value(r#"{"status": "ok", "data": []}"#), &["timestamp"]),
+            [Difference::Removed("/data/0".into())]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! # Entry API
+//!
+//! This module provides in-place editing of a single object member, in the
+//! style of `BTreeMap::entry`.
+
+use alloc::string::String;
+
+use crate::JsonValue;
+
+/// A member of an object that may or may not be present, from `JsonValue::entry`.
+#[derive(Debug)]
+pub enum Entry<'a> {
+    /// The member exists; this is its value
+    Occupied(&'a mut JsonValue),
+    /// The member does not exist yet
+    Vacant(VacantEntry<'a>),
+}
+
+/// An absent object member that can be inserted.
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    object: &'a mut JsonValue,
+    key: String,
+}
+
+impl JsonValue {
+    /// Gets the member `key` of an object for in-place editing.
+    ///
+    /// Returns `None` for values that are not objects. When a key is repeated,
+    /// the entry refers to its last occurrence, as `get` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_parser::JsonValue;
+    ///
+    /// let mut config = JsonValue::object().insert("retries", 3).build();
+    /// if let Some(entry) = config.entry("timeout") {
+    ///     entry.or_insert(JsonValue::from(30));
+    /// }
+    ///
+    /// assert_eq!(config.to_string(), r#"{"retries":3,"timeout":30}"#);
+    /// ```
+    pub fn entry(&mut self, key: &str) -> Option<Entry<'_>> {
+        if !matches!(self, JsonValue::Object(_) | JsonValue::Map(_)) {
+            return None;
+        }
+        if self.get(key).is_some() {
+            return self.get_mut(key).map(Entry::Occupied);
+        }
+        Some(Entry::Vacant(VacantEntry {
+            object: self,
+            key: key.into(),
+        }))
+    }
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the member's value, inserting `default` first if it is absent.
+    pub fn or_insert(self, default: JsonValue) -> &'a mut JsonValue {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the member's value, inserting the result of `default` first if
+    /// it is absent.
+    pub fn or_insert_with<F: FnOnce() -> JsonValue>(self, default: F) -> &'a mut JsonValue {
+        match self {
+            Entry::Occupied(value) => value,
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the member's value if it is present.
+    pub fn and_modify<F: FnOnce(&mut JsonValue)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(value) => {
+                f(value);
+                Entry::Occupied(value)
+            }
+            vacant => vacant,
+        }
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Returns the key that would be inserted.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Adds the member to the end of the object and returns its value.
+    pub fn insert(self, value: JsonValue) -> &'a mut JsonValue {
+        let VacantEntry { object, key } = self;
+        object.insert(key.clone(), value);
+        // The member was just added, so the lookup cannot fail
+        object.get_mut(&key).expect("inserted member")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::JsonDocument;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut config = value(r#"{"timeout": 5, "name": "api"}"#);
+
+        let occupied = config.entry("timeout").unwrap();
+        assert!(matches!(occupied, Entry::Occupied(_)));
+        assert_eq!(*occupied.or_insert(JsonValue::from(30)), JsonValue::from(5));
+
+        let vacant = config.entry("retries").unwrap();
+        assert!(matches!(&vacant, Entry::Vacant(entry) if entry.key() == "retries"));
+        *vacant.or_insert(JsonValue::from(1)) = JsonValue::from(2);
+
+        config
+            .entry("name")
+            .unwrap()
+            .and_modify(|name| *name = JsonValue::String(String::from("web")))
+            .or_insert(JsonValue::Null);
+        config
+            .entry("tags")
+            .unwrap()
+            .and_modify(|_| panic!("vacant entries are not modified"))
+            .or_insert_with(|| JsonValue::array().push("a").build());
+
+        assert_eq!(
+            config.to_string(),
+            r#"{"timeout":5,"name":"web","retries":2,"tags":["a"]}"#
+        );
+        assert!(value("[1]").entry("0").is_none());
+        assert!(JsonValue::Null.entry("a").is_none());
+    }
+}
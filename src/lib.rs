@@ -1,30 +1,137 @@
 //! # JSON Validator
 //!
 //! This library provides a parser for validating JSON structures.
+//!
+//! Without the default `std` feature the crate is `no_std` and needs only
+//! `alloc`; reading from files and readers is then unavailable.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::{BufRead, BufReader, Read};
+#[cfg(feature = "std")]
+use std::path::Path;
 
+use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
 use thiserror::Error;
 
+mod budget;
+mod builder;
+mod diagnostics;
+mod diff;
+mod entry;
+mod number;
+mod options;
+mod path;
+mod report;
+#[cfg(feature = "schema")]
+pub mod schema;
+mod ser;
+#[cfg(feature = "std")]
+mod stream;
+mod value;
+mod value_ref;
+mod visitor;
+
+pub use budget::Budget;
+pub use builder::{ArrayBuilder, IntoJsonValue, ObjectBuilder};
+pub use diff::Difference;
+pub use entry::{Entry, VacantEntry};
+pub use number::JsonNumber;
+pub use options::{DuplicateKeyPolicy, HashFloodGuard, ObjectBacking, ParseOptions};
+pub use path::PathError;
+pub use report::{ParseMetrics, ParseReport, ParseWarning};
+pub use ser::{IndentStyle, SerializeOptions};
+#[cfg(feature = "std")]
+pub use stream::{FeedResult, StreamValidator};
+pub use value::{JsonValue, JsonValueType, MergeStrategy, WalkAction};
+pub use value_ref::JsonValueRef;
+pub use visitor::JsonVisitor;
+
 /// Parser for JSON documents.
+///
+/// Together with the generated `Rule` enum, this exposes the grammar in
+/// `json.pest` for parsing fragments with `pest::Parser::parse`, such as a
+/// standalone `Rule::number`, without redeclaring the parser.
 #[derive(Parser)]
-#[grammar = "json.pest"] 
+#[grammar = "json.pest"]
 pub struct JsonParser;
 
 /// Represents a parsed JSON document.
 #[derive(Debug)]
 pub struct JsonDocument {
-    /// Raw content of the JSON document
+    /// Raw content of the JSON document, with leading and trailing whitespace
+    /// (including any trailing newline) removed
     pub content: String,
-    /// Type of the root JSON element (object, array)
+    /// Type of the root JSON element (object, array, or an allowed scalar)
     pub root_type: JsonRootType,
+    /// Whether the content was parsed with `ParseOptions::allow_js_numbers`;
+    /// conversions read it with the same number rule
+    pub allow_js_numbers: bool,
+}
+
+/// Results of validating newline-delimited JSON with an error limit.
+#[derive(Debug, Default)]
+pub struct ParsedLines {
+    /// Each processed line's 1-based number and parse result
+    pub results: Vec<(usize, Result<JsonDocument, JsonParseError>)>,
+    /// Whether processing stopped at the error limit before the end of input
+    pub aborted: bool,
 }
 
+/// The UTF-8 encoding of U+FEFF, which some editors write at the start of a file.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
 /// Represents the type of the root JSON element
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum JsonRootType {
     Object,
     Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+impl JsonRootType {
+    /// Returns the root type for a pest rule matched as a value, if any.
+    fn from_rule(rule: Rule) -> Option<Self> {
+        match rule {
+            Rule::object => Some(JsonRootType::Object),
+            Rule::array => Some(JsonRootType::Array),
+            Rule::string => Some(JsonRootType::String),
+            Rule::number | Rule::js_number => Some(JsonRootType::Number),
+            Rule::boolean => Some(JsonRootType::Boolean),
+            Rule::null => Some(JsonRootType::Null),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JsonRootType {
+    /// Formats the root type as a lowercase name, such as `object`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            JsonRootType::Object => "object",
+            JsonRootType::Array => "array",
+            JsonRootType::String => "string",
+            JsonRootType::Number => "number",
+            JsonRootType::Boolean => "boolean",
+            JsonRootType::Null => "null",
+        };
+        f.write_str(name)
+    }
 }
 
 /// Custom error for parsing failures.
@@ -36,12 +143,296 @@ pub enum JsonParseError {
         #[from]
         source: pest::error::Error<Rule>,
     },
-    /// Error for an empty JSON input.
+    /// Error for a common syntax mistake recognized after a parse failure.
+    ///
+    /// `expected` lists what would have been accepted at the error position,
+    /// such as `","`, `"}"`, or `"a JSON value"`.
+    #[error("{message} at line {line}, column {column}")]
+    Syntax {
+        message: String,
+        line: usize,
+        column: usize,
+        expected: Vec<String>,
+    },
+    /// Error for a backslash in a string followed by a character that does
+    /// not start a valid escape; `escape` is that character, or the backslash
+    /// itself when the input ends right after it.
+    #[error("Invalid escape '\\{escape}' at line {line}, column {column}")]
+    InvalidEscape {
+        escape: char,
+        line: usize,
+        column: usize,
+    },
+    /// Error for input that ends inside an object or array, as when a write
+    /// is interrupted. `opened_at` is the byte offset of the innermost
+    /// unclosed bracket in the input.
+    #[error("Unclosed {kind} opened at line {line}, column {column}")]
+    UnclosedContainer {
+        kind: JsonRootType,
+        opened_at: usize,
+        line: usize,
+        column: usize,
+    },
+    /// Error for a zero-length JSON input.
     #[error("Empty JSON input provided.")]
     EmptyJson,
+    /// Error for a JSON input containing only whitespace.
+    #[error("JSON input contains only whitespace.")]
+    WhitespaceOnly,
     /// Error for unexpected root type.
     #[error("Unexpected root type: {0}")]
     UnexpectedRootType(String),
+    /// Error returned when valid JSON does not match the target type.
+    #[cfg(feature = "serde")]
+    #[error("Deserialization error: {0}")]
+    Deserialize(#[from] serde_json::Error),
+    /// Error for a `\u` escape naming half of a UTF-16 surrogate pair without its partner.
+    #[error("Unpaired surrogate \\u{code:04X} at line {line}, column {column}")]
+    UnpairedSurrogate {
+        code: u32,
+        line: usize,
+        column: usize,
+    },
+    /// Error returned when reading input from a reader fails.
+    #[cfg(feature = "std")]
+    #[error("Read error: {0}")]
+    Read(#[from] std::io::Error),
+    /// Error returned when a file cannot be read.
+    #[cfg(feature = "std")]
+    #[error("Error reading file {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// Error for byte input that is not valid UTF-8.
+    #[error("Invalid UTF-8 input: {0}")]
+    InvalidUtf8(#[from] core::str::Utf8Error),
+    /// Error for input in an encoding other than UTF-8, recognized by its byte order mark.
+    #[error("Unsupported encoding {detected}: only UTF-8 input is supported")]
+    UnsupportedEncoding { detected: String },
+    /// Error for an object that repeats a key where unique keys are required.
+    #[error("Duplicate key: {key}")]
+    DuplicateKey { key: String },
+    /// Error for a JSON Pointer that does not resolve to a string value.
+    #[error("No string value at pointer '{0}'")]
+    NoStringAtPointer(String),
+    /// Error for an object rejected by the hash-flood guard.
+    #[error("Object at byte {offset} has {keys} keys in {bytes} bytes, rejected as a possible hash flood")]
+    HashFlood {
+        offset: usize,
+        keys: usize,
+        bytes: usize,
+    },
+    /// Error for a document with more values than `ParseOptions::max_values` allows.
+    #[error("Document has more than {limit} values")]
+    TooManyValues { limit: usize },
+    /// Error for input that exceeds one of the limits of a `Budget`.
+    #[error("Input exceeds budget: {limit} is {max}")]
+    BudgetExceeded { limit: &'static str, max: usize },
+    /// Error for a number too large for `f64`, with
+    /// `ParseOptions::reject_overflowing_numbers` set.
+    #[error("Number {token} is out of range at line {line}")]
+    NumberOutOfRange { token: String, line: usize },
+}
+
+/// A coarse, fixed classification of `JsonParseError` for metrics labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The input was empty or only whitespace
+    Empty,
+    /// The input is not well-formed JSON
+    Syntax,
+    /// The root value's type was not allowed
+    RootType,
+    /// Reading the input failed
+    Io,
+    /// A configured size or count limit was exceeded
+    Limit,
+    /// The input bytes were not UTF-8
+    Encoding,
+    /// Well-formed JSON was rejected for its contents, such as a duplicate key
+    Content,
+}
+
+impl ErrorCategory {
+    /// Returns a stable lowercase label for this category, such as `"syntax"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCategory::Empty => "empty",
+            ErrorCategory::Syntax => "syntax",
+            ErrorCategory::RootType => "root_type",
+            ErrorCategory::Io => "io",
+            ErrorCategory::Limit => "limit",
+            ErrorCategory::Encoding => "encoding",
+            ErrorCategory::Content => "content",
+        }
+    }
+}
+
+impl JsonParseError {
+    /// Returns the category of this error, for bucketing failures in metrics.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            JsonParseError::PestError { .. }
+            | JsonParseError::Syntax { .. }
+            | JsonParseError::InvalidEscape { .. }
+            | JsonParseError::UnclosedContainer { .. }
+            | JsonParseError::UnpairedSurrogate { .. } => ErrorCategory::Syntax,
+            JsonParseError::EmptyJson | JsonParseError::WhitespaceOnly => ErrorCategory::Empty,
+            JsonParseError::UnexpectedRootType(_) => ErrorCategory::RootType,
+            #[cfg(feature = "std")]
+            JsonParseError::Read(_) | JsonParseError::Io { .. } => ErrorCategory::Io,
+            JsonParseError::InvalidUtf8(_) | JsonParseError::UnsupportedEncoding { .. } => {
+                ErrorCategory::Encoding
+            }
+            JsonParseError::HashFlood { .. }
+            | JsonParseError::TooManyValues { .. }
+            | JsonParseError::BudgetExceeded { .. } => ErrorCategory::Limit,
+            #[cfg(feature = "serde")]
+            JsonParseError::Deserialize(_) => ErrorCategory::Content,
+            JsonParseError::DuplicateKey { .. }
+            | JsonParseError::NoStringAtPointer(_)
+            | JsonParseError::NumberOutOfRange { .. } => ErrorCategory::Content,
+        }
+    }
+
+    /// Returns friendly descriptions of the tokens that would have been
+    /// accepted at the error position, such as `","` or `"a JSON value"`.
+    ///
+    /// `Syntax` errors report their `expected` field; pest failures report
+    /// the rules pest tried there. Other errors yield an empty list.
+    pub fn expected(&self) -> Vec<String> {
+        match self {
+            JsonParseError::Syntax { expected, .. } => expected.clone(),
+            JsonParseError::PestError { source } => match &source.variant {
+                pest::error::ErrorVariant::ParsingError { positives, .. } => {
+                    diagnostics::describe_positives(positives)
+                }
+                pest::error::ErrorVariant::CustomError { .. } => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the 1-based line and column of the error, if it has one.
+    ///
+    /// Like the line and column in error messages, these count from the start
+    /// of the input, including any whitespace `parse` trims. Input decoded
+    /// from bytes counts from after a UTF-8 byte order mark.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            JsonParseError::PestError { source } => match source.line_col {
+                pest::error::LineColLocation::Pos(pos)
+                | pest::error::LineColLocation::Span(pos, _) => Some(pos),
+            },
+            JsonParseError::Syntax { line, column, .. }
+            | JsonParseError::InvalidEscape { line, column, .. }
+            | JsonParseError::UnclosedContainer { line, column, .. }
+            | JsonParseError::UnpairedSurrogate { line, column, .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    /// Renders up to `radius` characters either side of the error position,
+    /// with a caret under it on the following line, for sharing in bug reports.
+    ///
+    /// `input` must be the text that was parsed. The window never spans more
+    /// than the error's line. Errors without a position, such as `EmptyJson`,
+    /// yield an empty string.
+    pub fn minimal_context(&self, input: &str, radius: usize) -> String {
+        let Some((line, column)) = self.position() else {
+            return String::new();
+        };
+        let text: Vec<char> = input.lines().nth(line - 1).unwrap_or("").chars().collect();
+
+        let at = column - 1;
+        let start = at.saturating_sub(radius);
+        let end = (at + radius + 1).min(text.len());
+        let snippet: String = text.get(start..end).unwrap_or_default().iter().collect();
+        format!("{}\n{}^", snippet, " ".repeat(at - start))
+    }
+
+    /// Moves this error's position to count from the start of `input`, for
+    /// an error found in the slice of `input` that begins `offset` bytes in.
+    fn offset_by(self, input: &str, offset: usize) -> Self {
+        if offset == 0 {
+            return self;
+        }
+        let skipped = &input[..offset];
+        let shift = |line: usize, column: usize| {
+            let lines = skipped.matches('\n').count();
+            match line {
+                1 => (
+                    1 + lines,
+                    column + skipped.rsplit('\n').next().unwrap_or("").chars().count(),
+                ),
+                _ => (line + lines, column),
+            }
+        };
+
+        match self {
+            JsonParseError::PestError { source } => {
+                let moved = match source.location {
+                    pest::error::InputLocation::Pos(pos) => {
+                        pest::Position::new(input, pos + offset)
+                            .map(|pos| pest::error::Error::new_from_pos(source.variant, pos))
+                    }
+                    pest::error::InputLocation::Span((start, end)) => {
+                        pest::Span::new(input, start + offset, end + offset)
+                            .map(|span| pest::error::Error::new_from_span(source.variant, span))
+                    }
+                };
+                JsonParseError::PestError {
+                    source: moved.expect("error positions lie within the trimmed input"),
+                }
+            }
+            JsonParseError::Syntax {
+                message,
+                line,
+                column,
+                expected,
+            } => {
+                let (line, column) = shift(line, column);
+                JsonParseError::Syntax {
+                    message,
+                    line,
+                    column,
+                    expected,
+                }
+            }
+            JsonParseError::InvalidEscape {
+                escape,
+                line,
+                column,
+            } => {
+                let (line, column) = shift(line, column);
+                JsonParseError::InvalidEscape {
+                    escape,
+                    line,
+                    column,
+                }
+            }
+            JsonParseError::UnclosedContainer {
+                kind,
+                opened_at,
+                line,
+                column,
+            } => {
+                let (line, column) = shift(line, column);
+                JsonParseError::UnclosedContainer {
+                    kind,
+                    opened_at: opened_at + offset,
+                    line,
+                    column,
+                }
+            }
+            JsonParseError::UnpairedSurrogate { code, line, column } => {
+                let (line, column) = shift(line, column);
+                JsonParseError::UnpairedSurrogate { code, line, column }
+            }
+            error => error,
+        }
+    }
 }
 
 impl JsonDocument {
@@ -55,102 +446,1761 @@ impl JsonDocument {
     ///
     /// Returns a `JsonParseError` if the input does not conform to JSON grammar.
     pub fn parse(input: &str) -> Result<Self, JsonParseError> {
-        let trimmed_input = input.trim();
-        if trimmed_input.is_empty() {
-            return Err(JsonParseError::EmptyJson);
-        }
+        Self::parse_with_options(input, &ParseOptions::default())
+    }
+
+    /// Parses a JSON string, applying the checks configured in `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the input does not conform to JSON grammar
+    /// or violates one of the configured options.
+    pub fn parse_with_options(input: &str, options: &ParseOptions) -> Result<Self, JsonParseError> {
+        Self::parse_with_report(input, options).map(|(doc, _)| doc)
+    }
 
-        let pairs = JsonParser::parse(Rule::json, trimmed_input)?;
+    /// Parses a JSON string and returns the warnings collected along the way.
+    ///
+    /// Warnings describe valid input that may still cause trouble for
+    /// consumers, such as integers a JavaScript reader cannot hold exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` under the same conditions as `parse_with_options`.
+    pub fn parse_with_report(
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseReport), JsonParseError> {
+        let trimmed_input = Self::trim_input(input)?;
+        if !options.trim_input {
+            Self::reject_surrounding_whitespace(input, trimmed_input)?;
+        }
+        let mut report = ParseReport::default();
+        let (root_type, _) =
+            Self::with_trimmed(input, |trimmed| Self::check(trimmed, options, &mut report))?;
 
-        // Get the first pair (root)
-        let root = pairs.into_iter().next().ok_or(JsonParseError::EmptyJson)?;
+        let doc = JsonDocument {
+            content: trimmed_input.to_string(),
+            root_type,
+            allow_js_numbers: options.allow_js_numbers,
+        };
+        Ok((doc, report))
+    }
 
-        // Drill down to the actual root element inside the `json` rule
-        let root = root.into_inner().next().ok_or(JsonParseError::EmptyJson)?;
+    /// Parses a JSON string like `parse`, also measuring the input size, the
+    /// parse time, and the number of values.
+    ///
+    /// The timer covers only the grammar parse and the walk over its tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` under the same conditions as `parse`.
+    #[cfg(feature = "std")]
+    pub fn parse_with_metrics(input: &str) -> Result<(Self, ParseMetrics), JsonParseError> {
+        let trimmed_input = Self::trim_input(input)?;
+        let start = std::time::Instant::now();
+        let (root_type, value_count) = Self::with_trimmed(input, |trimmed| {
+            Self::check(
+                trimmed,
+                &ParseOptions::default(),
+                &mut ParseReport::default(),
+            )
+        })?;
+        let metrics = ParseMetrics {
+            input_bytes: input.len(),
+            parse_duration: start.elapsed(),
+            value_count,
+        };
 
-        let root_type = match root.as_rule() {
-            Rule::object => JsonRootType::Object,
-            Rule::array => JsonRootType::Array,
-            _ => {
-                return Err(JsonParseError::UnexpectedRootType(format!(
-                    "{:?}",
-                    root.as_rule()
-                )));
-            }
+        let doc = JsonDocument {
+            content: trimmed_input.to_string(),
+            root_type,
+            allow_js_numbers: false,
         };
+        Ok((doc, metrics))
+    }
+
+    /// Validates the input and reports its contents to `visitor` as a sequence
+    /// of events, without building a `JsonValue` tree.
+    ///
+    /// Any root value is accepted. Events are only dispatched once the whole
+    /// input has been validated, so a malformed document produces no events.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the input is not valid JSON.
+    pub fn visit<V: JsonVisitor>(input: &str, visitor: &mut V) -> Result<(), JsonParseError> {
+        Self::with_trimmed(input, |trimmed| {
+            visitor::dispatch(Self::root_pair(trimmed)?, visitor)
+        })
+    }
+
+    /// Reads a file and parses its contents with `parse`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` if the file cannot be read, `UnsupportedEncoding` if it
+    /// starts with a UTF-16 byte order mark, `InvalidUtf8` if it is not valid
+    /// UTF-8, or any error `parse` would return.
+    #[cfg(feature = "std")]
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self, JsonParseError> {
+        Self::parse_file_with_options(path, &ParseOptions::default())
+    }
+
+    /// Reads a file and parses its contents with `parse_with_options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `parse_file`, or any error
+    /// `parse_with_options` would return.
+    #[cfg(feature = "std")]
+    pub fn parse_file_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, JsonParseError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| JsonParseError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse_with_options(Self::decode_utf8(&bytes)?, options)
+    }
+
+    /// Reads a file with `tokio::fs` and parses its contents with `parse`.
+    ///
+    /// Only the read is asynchronous; parsing runs synchronously on the
+    /// current task once the contents are in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `parse_file`.
+    #[cfg(feature = "tokio")]
+    pub async fn parse_file_async<P: AsRef<Path>>(path: P) -> Result<Self, JsonParseError> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|source| JsonParseError::Io {
+                path: path.display().to_string(),
+                source,
+            })?;
+        Self::parse(Self::decode_utf8(&bytes)?)
+    }
+
+    /// Parses untrusted input, enforcing every limit in `budget`.
+    ///
+    /// The byte length is checked before parsing; depth, node count, and
+    /// string lengths are then checked in document order on the parse tree,
+    /// before the checks `parse` makes. The input is parsed only once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BudgetExceeded` naming the first limit violated, or any error
+    /// `parse` would return.
+    pub fn parse_within_budget(input: &str, budget: &Budget) -> Result<Self, JsonParseError> {
+        budget.check_bytes(input)?;
+        let trimmed_input = Self::trim_input(input)?;
+        let options = ParseOptions::default();
+        let (root_type, _) = Self::with_trimmed(input, |trimmed| {
+            let root = Self::root_pair_with(trimmed, options.root_rule())?;
+            budget.check_tree(root.clone())?;
+            Self::check_root(root, &options, &mut ParseReport::default())
+        })?;
 
         Ok(JsonDocument {
             content: trimmed_input.to_string(),
             root_type,
+            allow_js_numbers: options.allow_js_numbers,
         })
     }
 
-    /// Checks if the JSON document is valid without creating a full document.
-    pub fn is_valid(input: &str) -> bool {
-        !input.trim().is_empty() && JsonParser::parse(Rule::json, input.trim()).is_ok()
+    /// Validates newline-delimited JSON (JSON Lines), one document per line.
+    ///
+    /// Each non-blank line is parsed independently with `parse` and returned
+    /// with its 1-based line number. Blank lines, a trailing newline, and
+    /// `\r\n` line endings are accepted.
+    pub fn parse_lines(input: &str) -> Vec<(usize, Result<JsonDocument, JsonParseError>)> {
+        Self::ndjson_lines(input)
+            .map(|(number, line)| (number, Self::parse(line)))
+            .collect()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Validates newline-delimited JSON like `parse_lines`, stopping once
+    /// `max_errors` lines have failed.
+    ///
+    /// The returned `ParsedLines` holds every result up to and including the
+    /// last counted error, and sets `aborted` if lines were left unprocessed.
+    /// A `max_errors` of 0 is treated as 1, stopping at the first error.
+    pub fn parse_lines_with_limit(input: &str, max_errors: usize) -> ParsedLines {
+        let max_errors = max_errors.max(1);
+        let mut lines = Self::ndjson_lines(input).peekable();
+        let mut parsed = ParsedLines::default();
+        let mut errors = 0;
 
-    #[test]
-    fn test_valid_object_json() {
-        let json = r#"{"name": "test", "value": 42}"#;
-        let doc = JsonDocument::parse(json);
-        assert!(doc.is_ok());
-        if let Ok(doc) = doc {
-            assert_eq!(doc.root_type, JsonRootType::Object);
+        while errors < max_errors {
+            let Some((number, line)) = lines.next() else {
+                break;
+            };
+            let result = Self::parse(line);
+            errors += usize::from(result.is_err());
+            parsed.results.push((number, result));
         }
+        parsed.aborted = lines.peek().is_some();
+        parsed
     }
 
-    #[test]
-    fn test_valid_array_json() {
-        let json = r#"[1, 2, 3, "test"]"#;
-        let doc = JsonDocument::parse(json);
-        assert!(doc.is_ok());
-        if let Ok(doc) = doc {
-            assert_eq!(doc.root_type, JsonRootType::Array);
+    /// Parses a stream of concatenated JSON values, such as `{"a":1}{"b":2}`.
+    ///
+    /// Values may be separated by whitespace or nothing at all, and any type
+    /// of value is accepted, not just objects and arrays. Empty or
+    /// whitespace-only input yields no values.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any text after the last complete value is not itself a value.
+    pub fn parse_many(input: &str) -> Result<Vec<JsonValue>, JsonParseError> {
+        let mut pairs = JsonParser::parse(Rule::values, input)?;
+        let stream = pairs.next().ok_or(JsonParseError::EmptyJson)?;
+        let options = ParseOptions::default();
+        stream
+            .into_inner()
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(|pair| JsonValue::from_pair(pair, &options))
+            .collect()
+    }
+
+    /// Yields the non-blank lines of NDJSON input with their 1-based numbers.
+    fn ndjson_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+        input
+            .split('\n')
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.strip_suffix('\r').unwrap_or(line)))
+            .filter(|(_, line)| !line.trim().is_empty())
+    }
+
+    /// Counts the top-level elements of a JSON array read from `reader`.
+    ///
+    /// The input is scanned in fixed-size chunks while tracking only bracket,
+    /// brace, and string state, so memory use stays constant however large
+    /// the array is. This checks structure, not full validity: elements are
+    /// not parsed, so `[1, tru]` counts as two elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Read` if the reader fails, `EmptyJson` or `WhitespaceOnly` for
+    /// blank input after any UTF-8 byte order mark, as `validate_bytes` does,
+    /// `UnexpectedRootType` if the root is not an array, or
+    /// `Syntax` if the brackets are unbalanced or content follows the array.
+    #[cfg(feature = "std")]
+    pub fn count_array_elements_streaming<R: Read>(reader: R) -> Result<usize, JsonParseError> {
+        let mut reader = BufReader::new(reader);
+        let mut scanner = stream::ArrayCounter::default();
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            for &byte in chunk {
+                scanner.feed(byte)?;
+            }
+            let len = chunk.len();
+            reader.consume(len);
         }
+        scanner.finish()
     }
 
-    #[test]
-    fn test_empty_json() {
-        let json = r#"  "#;
-        assert!(matches!(
-            JsonDocument::parse(json),
-            Err(JsonParseError::EmptyJson)
-        ));
+    /// Renders the parse tree as an indented outline of rules and byte spans.
+    ///
+    /// This is a debugging aid for exploring how the grammar matches an input;
+    /// its output format is not part of the stable API. Leaf rules also show
+    /// the text they matched. If parsing fails, the parser's error message is
+    /// returned instead.
+    pub fn parse_trace(input: &str) -> String {
+        fn render(pair: Pair<Rule>, indent: usize, out: &mut String) {
+            let span = pair.as_span();
+            out.push_str(&format!(
+                "{:indent$}{:?} {}..{}",
+                "",
+                pair.as_rule(),
+                span.start(),
+                span.end(),
+                indent = indent * 2
+            ));
+            let mut children = pair.clone().into_inner().peekable();
+            if children.peek().is_none() {
+                out.push_str(&format!(" {:?}", span.as_str()));
+            }
+            out.push('\n');
+            for child in children {
+                render(child, indent + 1, out);
+            }
+        }
+
+        match JsonParser::parse(Rule::document, input) {
+            Ok(pairs) => {
+                let mut out = String::new();
+                for pair in pairs {
+                    render(pair, 0, &mut out);
+                }
+                out
+            }
+            Err(error) => format!("{}\n", error),
+        }
     }
 
-    #[test]
-    fn test_invalid_json() {
-        let json = r#"{"name": "test", "value": }"#;
-        assert!(matches!(
-            JsonDocument::parse(json),
-            Err(JsonParseError::PestError { .. })
-        ));
+    /// Returns the name of the grammar rule that matched the root value, such
+    /// as `"object"` or `"array"`.
+    ///
+    /// Unlike `parse`, any root value is accepted, so scalars report `"string"`,
+    /// `"number"`, `"boolean"`, or `"null"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the input is not valid JSON.
+    pub fn root_rule_name(input: &str) -> Result<String, JsonParseError> {
+        Self::with_trimmed(input, |trimmed| {
+            Ok(format!("{:?}", Self::root_pair(trimmed)?.as_rule()))
+        })
     }
 
-    #[test]
-    fn test_unexpected_root_type() {
-        // Assuming `Rule::json` doesn't accept primitive values like `true` as root
-        let json = r#"true"#;
-        assert!(matches!(
-            JsonDocument::parse(json),
-            Err(JsonParseError::UnexpectedRootType(_))
-        ));
+    /// Validates UTF-8 encoded bytes and returns the root type.
+    ///
+    /// The bytes are checked for UTF-8 once and then validated in place, so no
+    /// copy of the content is made. A leading UTF-8 byte order mark is skipped,
+    /// so a BOM alone is `EmptyJson` like zero bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedEncoding` if the bytes start with a UTF-16 byte
+    /// order mark, `InvalidUtf8` if they are not valid UTF-8, or any error
+    /// `parse` would return for the decoded text.
+    pub fn validate_bytes(input: &[u8]) -> Result<JsonRootType, JsonParseError> {
+        Self::validate(Self::decode_utf8(input)?)
+    }
+
+    /// Reads `reader` to the end and validates the bytes like `validate_bytes`,
+    /// so blank and BOM-only input gets the same errors as from a slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Read` if the reader fails, or any error `validate_bytes` would
+    /// return.
+    #[cfg(feature = "std")]
+    pub fn validate_reader<R: Read>(mut reader: R) -> Result<JsonRootType, JsonParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::validate_bytes(&bytes)
+    }
+
+    /// Validates the input like `parse` and returns only the root type.
+    ///
+    /// Unlike `parse`, this does not copy the input into an owned document,
+    /// which suits validation-only hot paths that still need the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` under the same conditions as `parse`.
+    pub fn validate(input: &str) -> Result<JsonRootType, JsonParseError> {
+        Self::with_trimmed(input, |trimmed| {
+            Self::check(
+                trimmed,
+                &ParseOptions::default(),
+                &mut ParseReport::default(),
+            )
+        })
+        .map(|(root_type, _)| root_type)
+    }
+
+    /// Checks that bytes are UTF-8, recognizing UTF-16 by its byte order mark
+    /// so it gets a clearer error than an invalid UTF-8 sequence. A UTF-8 byte
+    /// order mark is removed.
+    fn decode_utf8(bytes: &[u8]) -> Result<&str, JsonParseError> {
+        let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+        let detected = match bytes {
+            [0xFF, 0xFE, ..] => "UTF-16LE",
+            [0xFE, 0xFF, ..] => "UTF-16BE",
+            _ => return Ok(core::str::from_utf8(bytes)?),
+        };
+        Err(JsonParseError::UnsupportedEncoding {
+            detected: detected.to_string(),
+        })
+    }
+
+    /// Strips surrounding whitespace, rejecting input with nothing left.
+    ///
+    /// Zero-length input yields `EmptyJson`, while input made only of
+    /// whitespace yields `WhitespaceOnly`.
+    fn trim_input(input: &str) -> Result<&str, JsonParseError> {
+        if input.is_empty() {
+            return Err(JsonParseError::EmptyJson);
+        }
+        match input.trim() {
+            "" => Err(JsonParseError::WhitespaceOnly),
+            trimmed => Ok(trimmed),
+        }
+    }
+
+    /// Runs `f` on the input with surrounding whitespace trimmed, reporting
+    /// error positions against the untrimmed input.
+    fn with_trimmed<'a, T>(
+        input: &'a str,
+        f: impl FnOnce(&'a str) -> Result<T, JsonParseError>,
+    ) -> Result<T, JsonParseError> {
+        let trimmed = Self::trim_input(input)?;
+        let offset = input.len() - input.trim_start().len();
+        f(trimmed).map_err(|error| error.offset_by(input, offset))
+    }
+
+    /// Fails with a `Syntax` error pointing at any whitespace that `trim_input`
+    /// removed from around the root value.
+    fn reject_surrounding_whitespace(input: &str, trimmed: &str) -> Result<(), JsonParseError> {
+        let (offset, message, expected) = if input.trim_start().len() < input.len() {
+            (
+                0,
+                "leading whitespace before the root value",
+                "a JSON value",
+            )
+        } else if trimmed.len() < input.len() {
+            (
+                trimmed.len(),
+                "trailing whitespace after the root value",
+                "end of input",
+            )
+        } else {
+            return Ok(());
+        };
+        let (line, column) = pest::Position::new(input, offset)
+            .map(|pos| pos.line_col())
+            .unwrap_or((1, 1));
+        Err(JsonParseError::Syntax {
+            message: message.to_string(),
+            line,
+            column,
+            expected: vec![expected.to_string()],
+        })
+    }
+
+    /// Validates trimmed input against the grammar and options, returning the
+    /// root type and the number of values.
+    fn check(
+        trimmed_input: &str,
+        options: &ParseOptions,
+        report: &mut ParseReport,
+    ) -> Result<(JsonRootType, usize), JsonParseError> {
+        let root = Self::root_pair_with(trimmed_input, options.root_rule())?;
+        Self::check_root(root, options, report)
+    }
+
+    /// Checks an already parsed root element against the options, returning
+    /// the root type and the number of values.
+    fn check_root(
+        root: Pair<Rule>,
+        options: &ParseOptions,
+        report: &mut ParseReport,
+    ) -> Result<(JsonRootType, usize), JsonParseError> {
+        let values = options.enforce(&root, report)?;
+
+        match JsonRootType::from_rule(root.as_rule()) {
+            Some(root_type) if options.allowed_roots.contains(&root_type) => {
+                Ok((root_type, values))
+            }
+            Some(root_type) => Err(options.unexpected_root(root_type)),
+            None => Err(JsonParseError::UnexpectedRootType(format!(
+                "{:?}",
+                root.as_rule()
+            ))),
+        }
+    }
+
+    /// Validates the input with this crate's grammar, then deserializes it into `T`.
+    ///
+    /// This is stricter than calling `serde_json` directly: the document must
+    /// pass the same checks as `parse`, such as having an object or array root.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if validation fails, or `Deserialize` if the
+    /// document does not match the shape of `T`.
+    #[cfg(feature = "serde")]
+    pub fn parse_into<T: serde::de::DeserializeOwned>(input: &str) -> Result<T, JsonParseError> {
+        let doc = Self::parse(input)?;
+        Ok(serde_json::from_str(&doc.content)?)
+    }
+
+    /// Returns the content with no trailing newline.
+    ///
+    /// `parse` already strips surrounding whitespace, so this is the same text
+    /// as `content`; it also holds for documents built by hand.
+    pub fn content_trimmed(&self) -> &str {
+        self.content.trim_end_matches(['\r', '\n'])
+    }
+
+    /// Returns the content ending in exactly one `\n`, as expected by
+    /// tools that require a final newline.
+    pub fn content_with_trailing_newline(&self) -> String {
+        format!("{}\n", self.content_trimmed())
+    }
+
+    /// Returns the likely root type by looking at the first non-whitespace character.
+    ///
+    /// This does not validate the input: `{"unterminated` still reports
+    /// `JsonRootType::Object`. Use `parse` or `is_valid` when validity matters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmptyJson` for zero-length input, `WhitespaceOnly` for blank
+    /// input, or `UnexpectedRootType` if the first
+    /// character does not open an object or array.
+    pub fn sniff_root_type(input: &str) -> Result<JsonRootType, JsonParseError> {
+        match Self::trim_input(input)?.chars().next() {
+            Some('{') => Ok(JsonRootType::Object),
+            Some('[') => Ok(JsonRootType::Array),
+            Some(c) => Err(JsonParseError::UnexpectedRootType(c.to_string())),
+            None => Err(JsonParseError::EmptyJson),
+        }
+    }
+
+    /// Guesses whether possibly malformed input was meant to be an object or array.
+    ///
+    /// A non-failing variant of `sniff_root_type` for triaging broken payloads:
+    /// returns `None` for blank input or any other leading character.
+    pub fn guess_root_type(input: &str) -> Option<JsonRootType> {
+        Self::sniff_root_type(input).ok()
+    }
+
+    /// Builds the value tree for this document.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the content cannot be decoded.
+    pub fn to_value(&self) -> Result<JsonValue, JsonParseError> {
+        self.to_value_with_options(&ParseOptions::default())
+    }
+
+    /// Builds the value tree for this document as configured by `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the content cannot be decoded.
+    pub fn to_value_with_options(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<JsonValue, JsonParseError> {
+        JsonValue::from_pair(self.content_pair()?, options)
+    }
+
+    /// Builds a value tree that borrows strings and keys from this document.
+    ///
+    /// Cheaper than `to_value` for read-heavy scans, since only strings that
+    /// contain escape sequences are copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the content cannot be decoded.
+    pub fn to_value_ref(&self) -> Result<JsonValueRef<'_>, JsonParseError> {
+        JsonValueRef::from_pair(self.content_pair()?)
+    }
+
+    /// Returns the members of the root object in source order.
+    ///
+    /// Keys are decoded with escapes resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedRootType` if the root is not an object.
+    pub fn root_entries(&self) -> Result<Vec<(String, JsonValue)>, JsonParseError> {
+        let root = self.content_pair()?;
+        if root.as_rule() != Rule::object {
+            return Err(JsonParseError::UnexpectedRootType(format!(
+                "{:?}",
+                root.as_rule()
+            )));
+        }
+
+        let options = ParseOptions::default();
+        root.into_inner()
+            .map(|member| JsonValue::member_from_pair(member, &options, &mut 0))
+            .collect()
+    }
+
+    /// Parses the input against the `document` rule and returns the root element.
+    fn root_pair(input: &str) -> Result<Pair<'_, Rule>, JsonParseError> {
+        Self::root_pair_with(input, Rule::document)
+    }
+
+    /// Reparses this document's content and returns the root element.
+    ///
+    /// The content is read with the rule it was parsed with, so only a
+    /// document parsed with `allow_js_numbers` yields `js_number` values.
+    fn content_pair(&self) -> Result<Pair<'_, Rule>, JsonParseError> {
+        let rule = if self.allow_js_numbers {
+            Rule::lenient_json
+        } else {
+            Rule::document
+        };
+        Self::root_pair_with(&self.content, rule)
+    }
+
+    /// Parses the input against a whole-document rule, `document` or
+    /// `lenient_json`, and returns the root element.
+    fn root_pair_with(input: &str, rule: Rule) -> Result<Pair<'_, Rule>, JsonParseError> {
+        let pairs =
+            JsonParser::parse(rule, input).map_err(|error| diagnostics::diagnose(input, error))?;
+
+        // Get the first pair (root)
+        let root = pairs.into_iter().next().ok_or(JsonParseError::EmptyJson)?;
+
+        // Drill down to the actual root element inside the document rule
+        root.into_inner().next().ok_or(JsonParseError::EmptyJson)
+    }
+
+    /// Checks if the JSON document is valid without creating a full document.
+    pub fn is_valid(input: &str) -> bool {
+        Self::validate(input).is_ok()
+    }
+
+    /// Checks that the whole of `input` matches a single grammar rule, such as
+    /// `Rule::number` or `Rule::string`.
+    ///
+    /// No surrounding whitespace is allowed, so `" 1"` is not a valid number.
+    pub fn validate_fragment(input: &str, rule: Rule) -> bool {
+        JsonParser::parse(rule, input).is_ok_and(|pairs| pairs.as_str().len() == input.len())
+    }
+
+    /// Decodes a single JSON string literal, quotes included, into its contents.
+    ///
+    /// The result borrows from `input` when the literal has no escapes and is
+    /// allocated otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `input` is not exactly one string literal: `InvalidEscape`
+    /// for an illegal escape, `Syntax` for text after the closing quote
+    /// (including whitespace), and the errors of string decoding, such as
+    /// `UnpairedSurrogate`.
+    pub fn decode_string(input: &str) -> Result<Cow<'_, str>, JsonParseError> {
+        let mut pairs = JsonParser::parse(Rule::string, input)
+            .map_err(|error| diagnostics::diagnose(input, error))?;
+        let pair = pairs.next().ok_or(JsonParseError::EmptyJson)?;
+        let end = pair.as_span().end();
+        if end < input.len() {
+            let (line, column) = pest::Position::new(input, end)
+                .map(|pos| pos.line_col())
+                .unwrap_or((1, 1));
+            return Err(JsonParseError::Syntax {
+                message: "trailing characters after the string".to_string(),
+                line,
+                column,
+                expected: vec!["end of input".to_string()],
+            });
+        }
+        value::decode_string(pair)
+    }
+
+    /// Checks that the input is valid JSON whose root is of type `expected`.
+    ///
+    /// The first non-whitespace character is checked before any parsing, so
+    /// input with the wrong root type is rejected without a full parse.
+    /// Scalar types are accepted as `expected` even though `parse` rejects them.
+    pub fn is_valid_with_root(input: &str, expected: JsonRootType) -> bool {
+        let first = input.trim_start().chars().next();
+        let plausible = match expected {
+            JsonRootType::Object => first == Some('{'),
+            JsonRootType::Array => first == Some('['),
+            JsonRootType::String => first == Some('"'),
+            JsonRootType::Number => first.is_some_and(|c| c == '-' || c.is_ascii_digit()),
+            JsonRootType::Boolean => matches!(first, Some('t' | 'f')),
+            JsonRootType::Null => first == Some('n'),
+        };
+        let options = ParseOptions {
+            allowed_roots: BTreeSet::from([expected]),
+            ..ParseOptions::default()
+        };
+        plausible && Self::parse_with_options(input, &options).is_ok()
+    }
+
+    /// Checks whether the input is strict JSON that needs no dialect extensions.
+    ///
+    /// The grammar implements no JSONC or JSON5 relaxations, so documents that
+    /// rely on comments, trailing commas, single quotes, or unquoted keys return
+    /// `false`. Use this to flag files that need migrating to plain JSON.
+    ///
+    /// Every root type is accepted, so scalar documents such as `42` or
+    /// `null` are standard JSON here even though `parse` rejects them.
+    pub fn is_standard_json(input: &str) -> bool {
+        Self::parse_with_options(input, &ParseOptions::any_root()).is_ok()
+    }
+}
+
+impl FromStr for JsonDocument {
+    type Err = JsonParseError;
+
+    /// Parses a JSON string, allowing the `str::parse` form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_parser::{JsonDocument, JsonRootType};
+    ///
+    /// let doc: JsonDocument = r#"{"key": "value"}"#.parse().unwrap();
+    /// assert_eq!(doc.root_type, JsonRootType::Object);
+    /// ```
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        JsonDocument::parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_object_json() {
+        let json = r#"{"name": "test", "value": 42}"#;
+        let doc = JsonDocument::parse(json);
+        assert!(doc.is_ok());
+        if let Ok(doc) = doc {
+            assert_eq!(doc.root_type, JsonRootType::Object);
+        }
+    }
+
+    #[test]
+    fn test_valid_array_json() {
+        let json = r#"[1, 2, 3, "test"]"#;
+        let doc = JsonDocument::parse(json);
+        assert!(doc.is_ok());
+        if let Ok(doc) = doc {
+            assert_eq!(doc.root_type, JsonRootType::Array);
+        }
+    }
+
+    #[test]
+    fn test_empty_json() {
+        let json = r#""#;
+        assert!(matches!(
+            JsonDocument::parse(json),
+            Err(JsonParseError::EmptyJson)
+        ));
+    }
+
+    #[test]
+    fn test_whitespace_only_json() {
+        for json in ["  ", "\n", " \r\n\t "] {
+            assert!(matches!(
+                JsonDocument::parse(json),
+                Err(JsonParseError::WhitespaceOnly)
+            ));
+        }
+        assert!(matches!(
+            JsonDocument::validate_bytes(b"\n\n"),
+            Err(JsonParseError::WhitespaceOnly)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let json = r#"{"name": "test", "value": }"#;
+        assert!(matches!(
+            JsonDocument::parse(json),
+            Err(JsonParseError::PestError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unexpected_root_type() {
+        // The default options don't accept primitive values like `true` as root
+        let json = r#"true"#;
+        assert!(matches!(
+            JsonDocument::parse(json),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+    }
+
+    #[test]
+    fn test_guess_root_type() {
+        assert_eq!(
+            JsonDocument::guess_root_type("{\"a\": [1,, }"),
+            Some(JsonRootType::Object)
+        );
+        assert_eq!(
+            JsonDocument::guess_root_type("\t[garbage"),
+            Some(JsonRootType::Array)
+        );
+        assert_eq!(JsonDocument::guess_root_type("'oops'"), None);
+        assert_eq!(JsonDocument::guess_root_type("   "), None);
+    }
+
+    #[test]
+    fn test_sniff_root_type() {
+        assert_eq!(
+            JsonDocument::sniff_root_type("  {\"key\": 1}").unwrap(),
+            JsonRootType::Object
+        );
+        assert_eq!(
+            JsonDocument::sniff_root_type("\n[1, 2").unwrap(),
+            JsonRootType::Array
+        );
+        assert!(matches!(
+            JsonDocument::sniff_root_type("true"),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+    }
+
+    #[test]
+    fn test_root_entries() {
+        let doc = JsonDocument::parse(r#"{"b": 1, "a\u0041": [true]}"#).unwrap();
+        let entries = doc.root_entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "b");
+        assert_eq!(entries[1].0, "aA");
+        assert_eq!(entries[1].1, JsonValue::Array(vec![JsonValue::Bool(true)]));
+
+        let doc = JsonDocument::parse("[1]").unwrap();
+        assert!(matches!(
+            doc.root_entries(),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_flood_guard() {
+        let options = ParseOptions {
+            hash_flood_guard: Some(HashFloodGuard {
+                min_keys: 100,
+                max_keys_per_byte: 0.1,
+            }),
+            ..ParseOptions::default()
+        };
+
+        let members: Vec<String> = (0..200).map(|i| format!("\"{}\":0", i)).collect();
+        let flood = format!("{{{}}}", members.join(","));
+        assert!(JsonDocument::parse(&flood).is_ok());
+        assert!(matches!(
+            JsonDocument::parse_with_options(&flood, &options),
+            Err(JsonParseError::HashFlood { keys: 200, .. })
+        ));
+
+        let members: Vec<String> = (0..200)
+            .map(|i| format!("\"setting_{}\": \"a reasonably sized value\"", i))
+            .collect();
+        let normal = format!("{{{}}}", members.join(", "));
+        assert!(JsonDocument::parse_with_options(&normal, &options).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_into() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            port: u16,
+        }
+
+        let config: Config = JsonDocument::parse_into(r#"{"name": "web", "port": 80}"#).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                name: "web".to_string(),
+                port: 80
+            }
+        );
+
+        assert!(matches!(
+            JsonDocument::parse_into::<Config>(r#"{"name": "web"}"#),
+            Err(JsonParseError::Deserialize(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_into_is_stricter_than_serde() {
+        // serde_json accepts a bare scalar, but the strict grammar requires a container root
+        assert_eq!(serde_json::from_str::<u32>("42").unwrap(), 42);
+        assert!(matches!(
+            JsonDocument::parse_into::<u32>("42"),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+    }
+
+    #[test]
+    fn test_object_backing() {
+        let doc = JsonDocument::parse(r#"{"b": 1, "a": {"c": true}, "b": 2}"#).unwrap();
+
+        let ordered = doc.to_value().unwrap();
+        assert!(matches!(ordered, JsonValue::Object(ref members) if members.len() == 3));
+        assert_eq!(
+            ordered.get("b"),
+            Some(&JsonValue::Number(JsonNumber::I64(2)))
+        );
+        assert_eq!(
+            ordered.get("a").and_then(|a| a.get("c")),
+            Some(&JsonValue::Bool(true))
+        );
+
+        let options = ParseOptions {
+            object_backing: ObjectBacking::Map,
+            ..ParseOptions::default()
+        };
+        let map = doc.to_value_with_options(&options).unwrap();
+        assert!(matches!(map, JsonValue::Map(ref members) if members.len() == 2));
+        assert_eq!(map.get("b"), Some(&JsonValue::Number(JsonNumber::I64(2))));
+        assert_eq!(
+            map.get("a").and_then(|a| a.get("c")),
+            Some(&JsonValue::Bool(true))
+        );
+        assert_eq!(map.get("missing"), None);
+    }
+
+    #[test]
+    fn test_visit_counts_strings() {
+        #[derive(Default)]
+        struct StringCounter {
+            strings: usize,
+            keys: usize,
+            depth: usize,
+            max_depth: usize,
+        }
+
+        impl JsonVisitor for StringCounter {
+            fn start_object(&mut self) {
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+            }
+            fn end_object(&mut self) {
+                self.depth -= 1;
+            }
+            fn key(&mut self, _key: &str) {
+                self.keys += 1;
+            }
+            fn string(&mut self, _value: &str) {
+                self.strings += 1;
+            }
+        }
+
+        let mut counter = StringCounter::default();
+        let input = r#"[{"name": "a", "tags": ["x", "y"]}, {"name": "b", "meta": {"n": 1}}]"#;
+        JsonDocument::visit(input, &mut counter).unwrap();
+        assert_eq!(counter.strings, 4);
+        assert_eq!(counter.keys, 5);
+        assert_eq!(counter.depth, 0);
+        assert_eq!(counter.max_depth, 2);
+
+        assert!(JsonDocument::visit("[\"a\",", &mut StringCounter::default()).is_err());
+
+        let mut counter = StringCounter::default();
+        assert!(JsonDocument::visit(r#"["a", "\uD800x"]"#, &mut counter).is_err());
+        assert_eq!(counter.strings, 0);
+    }
+
+    #[test]
+    fn test_surrounding_whitespace() {
+        let strict = ParseOptions {
+            trim_input: false,
+            ..ParseOptions::default()
+        };
+
+        // Structural whitespace between tokens is always allowed
+        let spaced = "{ \"a\" :\t[ 1 ,\r\n 2 ] }";
+        assert!(JsonDocument::parse_with_options(spaced, &strict).is_ok());
+
+        // Surrounding whitespace is trimmed by default but rejected when strict
+        for input in ["  {}", "{}\n", "\t[1]\r\n"] {
+            assert!(JsonDocument::parse(input).is_ok());
+            assert!(matches!(
+                JsonDocument::parse_with_options(input, &strict),
+                Err(JsonParseError::Syntax { .. })
+            ));
+        }
+
+        match JsonDocument::parse_with_options("[1,\n 2]  ", &strict) {
+            Err(JsonParseError::Syntax {
+                message,
+                line,
+                column,
+                expected,
+            }) => {
+                assert_eq!(message, "trailing whitespace after the root value");
+                assert_eq!((line, column), (2, 4));
+                assert_eq!(expected, ["end of input"]);
+            }
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let values = JsonDocument::parse_many(r#"{"a":1}{"b":2}"#).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1].to_string(), r#"{"b":2}"#);
+
+        let values = JsonDocument::parse_many("42 \n [true, null]\n").unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0], JsonValue::Number(JsonNumber::I64(42)));
+        assert_eq!(values[1].to_string(), "[true,null]");
+
+        assert!(JsonDocument::parse_many("  ").unwrap().is_empty());
+        assert!(matches!(
+            JsonDocument::parse_many(r#"{"a":1} [2] }"#),
+            Err(JsonParseError::PestError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_string() {
+        let input = r#""plain text""#;
+        let decoded = JsonDocument::decode_string(input).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(_)));
+        assert_eq!(decoded, "plain text");
+        assert_eq!(decoded.as_ptr(), input[1..].as_ptr());
+
+        let decoded = JsonDocument::decode_string(r#""tab\there \u00e9""#).unwrap();
+        assert!(matches!(decoded, Cow::Owned(_)));
+        assert_eq!(decoded, "tab\there \u{e9}");
+
+        assert!(matches!(
+            JsonDocument::decode_string(r#""a" "b""#),
+            Err(JsonParseError::Syntax { column: 4, .. })
+        ));
+        assert!(matches!(
+            JsonDocument::decode_string(r#""a\x""#),
+            Err(JsonParseError::InvalidEscape { escape: 'x', .. })
+        ));
+        assert!(JsonDocument::decode_string(r#" "a""#).is_err());
+        assert!(JsonDocument::decode_string("42").is_err());
+    }
+
+    #[test]
+    fn test_error_category() {
+        let category = |input: &str| JsonDocument::parse(input).unwrap_err().category();
+
+        assert_eq!(category(""), ErrorCategory::Empty);
+        assert_eq!(category(" \n"), ErrorCategory::Empty);
+        assert_eq!(category("[1,]"), ErrorCategory::Syntax);
+        assert_eq!(category("{\"a\" 1}"), ErrorCategory::Syntax);
+        assert_eq!(category("42"), ErrorCategory::RootType);
+        assert_eq!(
+            JsonDocument::validate_bytes(b"\xFF\xFE[]")
+                .unwrap_err()
+                .category(),
+            ErrorCategory::Encoding
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            JsonDocument::parse_file("/nonexistent/file.json")
+                .unwrap_err()
+                .category(),
+            ErrorCategory::Io
+        );
+        assert_eq!(
+            JsonDocument::parse_within_budget(
+                "[[1]]",
+                &Budget {
+                    max_depth: 1,
+                    ..Budget::default()
+                }
+            )
+            .unwrap_err()
+            .category(),
+            ErrorCategory::Limit
+        );
+        assert_eq!(
+            JsonParseError::DuplicateKey { key: "a".into() }.category(),
+            ErrorCategory::Content
+        );
+        assert_eq!(ErrorCategory::RootType.name(), "root_type");
+    }
+
+    #[test]
+    fn test_max_values() {
+        let options = ParseOptions {
+            max_values: Some(4),
+            ..ParseOptions::default()
+        };
+        assert!(JsonDocument::parse_with_options(r#"{"a": [1, 2]}"#, &options).is_ok());
+        assert!(matches!(
+            JsonDocument::parse_with_options("[1, 2, 3, 4]", &options),
+            Err(JsonParseError::TooManyValues { limit: 4 })
+        ));
+
+        // Building a value tree stops at the first value over the limit
+        let doc = JsonDocument::parse("[1, [2, 3], 4]").unwrap();
+        assert!(matches!(
+            doc.to_value_with_options(&options),
+            Err(JsonParseError::TooManyValues { limit: 4 })
+        ));
+        assert!(doc.to_value().is_ok());
+    }
+
+    #[test]
+    fn test_preserve_number_text() {
+        let input = "[1.0000000000000002, 100000000000000000000, 1.50, -0, 1E+2]";
+        let options = ParseOptions {
+            preserve_number_text: true,
+            ..ParseOptions::default()
+        };
+        let doc = JsonDocument::parse(input).unwrap();
+
+        let preserved = doc.to_value_with_options(&options).unwrap();
+        assert_eq!(
+            preserved.to_string(),
+            "[1.0000000000000002,100000000000000000000,1.50,-0,1E+2]"
+        );
+        assert_eq!(
+            preserved.pointer("/0"),
+            Some(&JsonValue::Number(JsonNumber::Raw(
+                "1.0000000000000002".to_string()
+            )))
+        );
+        assert_eq!(preserved, doc.to_value().unwrap());
+        assert_eq!(
+            doc.to_value().unwrap().to_string(),
+            "[1.0000000000000002,1e20,1.5,-0,100]"
+        );
+    }
+
+    #[test]
+    fn test_allow_js_numbers() {
+        let lenient = ParseOptions {
+            allow_js_numbers: true,
+            ..ParseOptions::default()
+        };
+        let number = |input: &str| {
+            let doc = JsonDocument::parse_with_options(input, &lenient).unwrap();
+            match doc.to_value_with_options(&lenient).unwrap().pointer("/0") {
+                Some(JsonValue::Number(n)) => n.as_f64(),
+                other => panic!("expected a number in {}, got {:?}", input, other),
+            }
+        };
+
+        assert!(number("[NaN]").is_nan());
+        assert_eq!(number("[Infinity]"), f64::INFINITY);
+        assert_eq!(number("[-Infinity]"), f64::NEG_INFINITY);
+        assert_eq!(number("[+1.5]"), 1.5);
+        assert_eq!(number("[+7]"), 7.0);
+
+        let doc = JsonDocument::parse_with_options(r#"{"a": [NaN, +2]}"#, &lenient).unwrap();
+        assert_eq!(doc.to_value().unwrap().to_string(), r#"{"a":[null,2]}"#);
+        assert_eq!(
+            JsonValue::from(doc.to_value_ref().unwrap()).to_string(),
+            r#"{"a":[null,2]}"#
+        );
+        assert_eq!(doc.root_entries().unwrap().len(), 1);
+
+        // A document not parsed with the flag is read as strict JSON
+        let doc = JsonDocument {
+            content: "[NaN]".to_string(),
+            root_type: JsonRootType::Array,
+            allow_js_numbers: false,
+        };
+        assert!(doc.to_value().is_err());
+        assert!(doc.to_value_ref().is_err());
+
+        for input in [
+            "[NaN]",
+            "[Infinity]",
+            "[-Infinity]",
+            "[+1]",
+            "[+-1]",
+            "[nan]",
+        ] {
+            assert!(
+                JsonDocument::parse(input).is_err(),
+                "{} should be rejected",
+                input
+            );
+        }
+        assert!(JsonDocument::parse_with_options("[+-1]", &lenient).is_err());
+        assert!(JsonDocument::parse_with_options("[Inf]", &lenient).is_err());
+    }
+
+    #[test]
+    fn test_overflowing_numbers() {
+        let input = "[1,\n 1e400]";
+        let doc = JsonDocument::parse(input).unwrap();
+
+        let value = doc.to_value().unwrap();
+        assert_eq!(
+            value.pointer("/1"),
+            Some(&JsonValue::Number(JsonNumber::Raw("1e400".to_string())))
+        );
+        assert_eq!(value.to_string(), "[1,1e400]");
+
+        let strict = ParseOptions {
+            reject_overflowing_numbers: true,
+            ..ParseOptions::default()
+        };
+        match doc.to_value_with_options(&strict) {
+            Err(JsonParseError::NumberOutOfRange { token, line }) => {
+                assert_eq!(token, "1e400");
+                assert_eq!(line, 2);
+            }
+            other => panic!("expected NumberOutOfRange, got {:?}", other),
+        }
+        assert!(JsonDocument::parse("[1e308]")
+            .unwrap()
+            .to_value_with_options(&strict)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_minimal_context() {
+        let input = r#"{"name": "widget", "tags": ["a" "b"], "count": 3}"#;
+        let error = JsonDocument::parse(input).unwrap_err();
+        assert_eq!(
+            error.minimal_context(input, 6),
+            " [\"a\" \"b\"], \"\n      ^"
+        );
+
+        let input = "[\n  1,\n  2,\n]";
+        let error = JsonDocument::parse(input).unwrap_err();
+        assert_eq!(error.minimal_context(input, 10), "]\n^");
+
+        assert_eq!(JsonParseError::EmptyJson.minimal_context("", 5), "");
+    }
+
+    #[test]
+    fn test_position_counts_leading_whitespace() {
+        let input = "\n\n{\n  \"a\": 1,\n  \"b\" 2}";
+        let error = JsonDocument::parse(input).unwrap_err();
+        assert_eq!(error.position(), Some((5, 7)));
+        assert!(
+            error.to_string().ends_with("at line 5, column 7"),
+            "{}",
+            error
+        );
+
+        let input = "  [1, @]";
+        let error = JsonDocument::parse(input).unwrap_err();
+        assert!(matches!(error, JsonParseError::PestError { .. }));
+        assert_eq!(error.position(), Some((1, 7)));
+        assert_eq!(error.minimal_context(input, 2), ", @]\n  ^");
+
+        let error = JsonDocument::validate_bytes(b"\xEF\xBB\xBF [1,]").unwrap_err();
+        assert_eq!(error.position(), Some((1, 5)));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            JsonDocument::validate("  [1, {\"a\": null}]\n").unwrap(),
+            JsonRootType::Array
+        );
+        assert!(matches!(
+            JsonDocument::validate("{\"a\": 1,}"),
+            Err(JsonParseError::Syntax { .. })
+        ));
+        assert!(matches!(
+            JsonDocument::validate("42"),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_bytes() {
+        assert_eq!(
+            JsonDocument::validate_bytes(b" {\"key\": [1, 2]}\n").unwrap(),
+            JsonRootType::Object
+        );
+        assert!(matches!(
+            JsonDocument::validate_bytes(b"[\"\xff\"]"),
+            Err(JsonParseError::InvalidUtf8(_))
+        ));
+        assert!(matches!(
+            JsonDocument::validate_bytes(b"\xFF\xFE[\x001\x00]\x00"),
+            Err(JsonParseError::UnsupportedEncoding { detected }) if detected == "UTF-16LE"
+        ));
+        assert!(matches!(
+            JsonDocument::validate_bytes(b"\xFE\xFF\x00[\x001\x00]"),
+            Err(JsonParseError::UnsupportedEncoding { detected }) if detected == "UTF-16BE"
+        ));
+        assert!(matches!(
+            JsonDocument::validate_bytes(b"[1,]"),
+            Err(JsonParseError::Syntax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_blank_input_consistency() {
+        let bom_only = b"\xEF\xBB\xBF";
+        let bom_blank = b"\xEF\xBB\xBF \r\n";
+        let cases: [(&[u8], _); 5] = [
+            (b"", "EmptyJson"),
+            (b" \n\t", "WhitespaceOnly"),
+            (bom_only, "EmptyJson"),
+            (bom_blank, "WhitespaceOnly"),
+            (b"\xEF\xBB\xBF[1, 2]", "Ok"),
+        ];
+        fn outcome<T>(result: Result<T, JsonParseError>) -> &'static str {
+            match result {
+                Ok(_) => "Ok",
+                Err(JsonParseError::EmptyJson) => "EmptyJson",
+                Err(JsonParseError::WhitespaceOnly) => "WhitespaceOnly",
+                Err(other) => panic!("unexpected error {:?}", other),
+            }
+        }
+
+        for (input, expected) in cases {
+            assert_eq!(outcome(JsonDocument::validate_bytes(input)), expected);
+            #[cfg(feature = "std")]
+            assert_eq!(outcome(JsonDocument::validate_reader(input)), expected);
+            #[cfg(feature = "std")]
+            assert_eq!(
+                outcome(JsonDocument::count_array_elements_streaming(input)),
+                expected
+            );
+            if !input.starts_with(UTF8_BOM) {
+                let text = core::str::from_utf8(input).unwrap();
+                assert_eq!(outcome(JsonDocument::validate(text)), expected);
+                assert_eq!(outcome(JsonDocument::parse(text)), expected);
+            }
+        }
+        #[cfg(feature = "std")]
+        assert_eq!(
+            JsonDocument::count_array_elements_streaming(&b"\xEF\xBB\xBF[1, 2]"[..]).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_unsafe_integer_warning() {
+        let input = "[9007199254740991, 9007199254740993, -9007199254740993, 1e20, 12345678901234567890123]";
+        let options = ParseOptions {
+            warn_unsafe_integers: true,
+            ..ParseOptions::default()
+        };
+
+        let (_, report) = JsonDocument::parse_with_report(input, &options).unwrap();
+        let tokens: Vec<&str> = report
+            .warnings
+            .iter()
+            .map(|w| match w {
+                ParseWarning::UnsafeInteger { token, .. } => token.as_str(),
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                "9007199254740993",
+                "-9007199254740993",
+                "12345678901234567890123"
+            ]
+        );
+        assert!(matches!(
+            &report.warnings[0],
+            ParseWarning::UnsafeInteger {
+                line: 1,
+                column: 20,
+                ..
+            }
+        ));
+
+        let (_, report) = JsonDocument::parse_with_report(input, &ParseOptions::default()).unwrap();
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_trailing_newline_handling() {
+        let doc = JsonDocument::parse("{\"key\": 1}\n\n").unwrap();
+        assert_eq!(doc.content, "{\"key\": 1}");
+        assert_eq!(doc.content_trimmed(), "{\"key\": 1}");
+        assert_eq!(doc.content_with_trailing_newline(), "{\"key\": 1}\n");
+
+        let doc = JsonDocument {
+            content: "[1]\r\n".to_string(),
+            root_type: JsonRootType::Array,
+            allow_js_numbers: false,
+        };
+        assert_eq!(doc.content_trimmed(), "[1]");
+        assert_eq!(doc.content_with_trailing_newline(), "[1]\n");
+    }
+
+    #[test]
+    fn test_allowed_roots() {
+        let with_roots = |roots: &[JsonRootType]| ParseOptions {
+            allowed_roots: roots.iter().copied().collect(),
+            ..ParseOptions::default()
+        };
+
+        // The default matches plain `parse`: objects and arrays only
+        let options = ParseOptions::default();
+        assert!(JsonDocument::parse_with_options("[1]", &options).is_ok());
+        assert!(JsonDocument::parse_with_options("42", &options).is_err());
+
+        let options = with_roots(&[JsonRootType::Object]);
+        assert!(JsonDocument::parse_with_options("{}", &options).is_ok());
+        match JsonDocument::parse_with_options("[]", &options) {
+            Err(JsonParseError::UnexpectedRootType(message)) => {
+                assert_eq!(message, "array (allowed: object)")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let options = with_roots(&[
+            JsonRootType::Number,
+            JsonRootType::String,
+            JsonRootType::Null,
+        ]);
+        let doc = JsonDocument::parse_with_options("  42 ", &options).unwrap();
+        assert_eq!(doc.root_type, JsonRootType::Number);
+        assert_eq!(
+            doc.to_value().unwrap(),
+            JsonValue::Number(JsonNumber::I64(42))
+        );
+        assert_eq!(
+            JsonDocument::parse_with_options(r#""text""#, &options)
+                .unwrap()
+                .root_type,
+            JsonRootType::String
+        );
+        assert_eq!(
+            JsonDocument::parse_with_options("null", &options)
+                .unwrap()
+                .root_type,
+            JsonRootType::Null
+        );
+        match JsonDocument::parse_with_options("{}", &options) {
+            Err(JsonParseError::UnexpectedRootType(message)) => {
+                assert_eq!(message, "object (allowed: null, number, string)")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let options = with_roots(&[]);
+        assert!(JsonDocument::parse_with_options("{}", &options).is_err());
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = "{\"id\": 1}\r\n\n[1, 2]\n{\"id\": }\r\n  \n{\"id\": 4}\n";
+        let results = JsonDocument::parse_lines(input);
+
+        let lines: Vec<usize> = results.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [1, 3, 4, 6]);
+        assert!(results[0].1.is_ok());
+        assert_eq!(
+            results[1].1.as_ref().unwrap().root_type,
+            JsonRootType::Array
+        );
+        assert!(matches!(
+            results[2].1,
+            Err(JsonParseError::PestError { .. })
+        ));
+        assert_eq!(results[3].1.as_ref().unwrap().content, "{\"id\": 4}");
+
+        assert!(JsonDocument::parse_lines("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_lines_with_limit() {
+        let input = "[1]\n{\n[2]\n]\n[3]\n{bad}\n[4]\n";
+
+        let parsed = JsonDocument::parse_lines_with_limit(input, 2);
+        assert!(parsed.aborted);
+        let lines: Vec<usize> = parsed.results.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [1, 2, 3, 4]);
+        assert_eq!(parsed.results.iter().filter(|(_, r)| r.is_err()).count(), 2);
+
+        let parsed = JsonDocument::parse_lines_with_limit(input, 4);
+        assert!(!parsed.aborted);
+        assert_eq!(parsed.results.len(), 7);
+
+        let parsed = JsonDocument::parse_lines_with_limit("[1]\n[2]\n", 1);
+        assert!(!parsed.aborted);
+        assert_eq!(parsed.results.len(), 2);
+
+        let parsed = JsonDocument::parse_lines_with_limit(input, 0);
+        assert!(parsed.aborted);
+        assert_eq!(parsed.results.len(), 2);
+        assert!(parsed.results[1].1.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_count_array_elements_streaming() {
+        let items: Vec<String> = (0..10_000)
+            .map(|i| format!("{{\"id\": {}, \"tags\": [\"a,b\", \"]\"]}}", i))
+            .collect();
+        let input = format!("[\n{}\n]\n", items.join(",\n"));
+        assert_eq!(
+            JsonDocument::count_array_elements_streaming(input.as_bytes()).unwrap(),
+            10_000
+        );
+
+        let count = |input: &str| JsonDocument::count_array_elements_streaming(input.as_bytes());
+        assert_eq!(count("[]").unwrap(), 0);
+        assert_eq!(count(r#" ["\"]", [1, 2], {"a": [3]}] "#).unwrap(), 3);
+        assert!(matches!(
+            count("{}"),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+        assert!(matches!(count("[1, 2"), Err(JsonParseError::Syntax { .. })));
+        assert!(matches!(
+            count("[1] [2]"),
+            Err(JsonParseError::Syntax { .. })
+        ));
+        assert!(matches!(count(""), Err(JsonParseError::EmptyJson)));
+        assert!(matches!(count(" \n"), Err(JsonParseError::WhitespaceOnly)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_parse_with_metrics() {
+        let input = "  {\"a\": [1, 2, {\"b\": null}], \"c\": \"x\"}\n";
+        let (doc, metrics) = JsonDocument::parse_with_metrics(input).unwrap();
+
+        assert_eq!(doc.content, input.trim());
+        assert_eq!(metrics.input_bytes, input.len());
+        assert_eq!(metrics.value_count, 7);
+        assert!(metrics.parse_duration < std::time::Duration::from_secs(1));
+        assert!(matches!(
+            JsonDocument::parse_with_metrics("[1,"),
+            Err(JsonParseError::UnclosedContainer { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_stream_validator() {
+        let mut validator = StreamValidator::new();
+        assert!(matches!(
+            validator.feed(" {\"name\": \"a\\\"}"),
+            FeedResult::NeedMore
+        ));
+        assert!(matches!(
+            validator.feed("b\", \"items\": [1, {"),
+            FeedResult::NeedMore
+        ));
+        assert!(matches!(
+            validator.feed("}]}\n"),
+            FeedResult::Complete(JsonRootType::Object)
+        ));
+        assert!(matches!(
+            validator.feed(" "),
+            FeedResult::Complete(JsonRootType::Object)
+        ));
+        assert!(matches!(validator.feed("{}"), FeedResult::Invalid(_)));
+        assert!(matches!(validator.feed(" "), FeedResult::Invalid(_)));
+
+        let feed_all = |chunks: &[&str]| {
+            let mut validator = StreamValidator::new();
+            chunks
+                .iter()
+                .map(|chunk| validator.feed(chunk))
+                .last()
+                .unwrap()
+        };
+        assert!(matches!(feed_all(&["[1, 2", "}"]), FeedResult::Invalid(_)));
+        assert!(matches!(
+            feed_all(&["{\"a\" 1", "}"]),
+            FeedResult::Invalid(_)
+        ));
+        assert!(matches!(feed_all(&["[\"]", "\""]), FeedResult::NeedMore));
+        assert!(matches!(feed_all(&["  "]), FeedResult::NeedMore));
+        // Roots the options reject fail on their first byte
+        for chunks in [&["tr"][..], &["1"], &["tr", "ue"], &["\"ab"], &[" n"]] {
+            assert!(
+                matches!(
+                    feed_all(chunks),
+                    FeedResult::Invalid(JsonParseError::UnexpectedRootType(_))
+                ),
+                "{:?}",
+                chunks
+            );
+        }
+
+        let feed_all = |chunks: &[&str]| {
+            let mut validator = StreamValidator::with_options(ParseOptions {
+                allowed_roots: BTreeSet::from([
+                    JsonRootType::Number,
+                    JsonRootType::Boolean,
+                    JsonRootType::Null,
+                    JsonRootType::String,
+                ]),
+                ..ParseOptions::default()
+            });
+            chunks
+                .iter()
+                .map(|chunk| validator.feed(chunk))
+                .last()
+                .unwrap()
+        };
+        assert!(matches!(
+            feed_all(&["tr", "ue"]),
+            FeedResult::Complete(JsonRootType::Boolean)
+        ));
+        assert!(matches!(feed_all(&["-1", "."]), FeedResult::NeedMore));
+        assert!(matches!(
+            feed_all(&["-1", ".5"]),
+            FeedResult::Complete(JsonRootType::Number)
+        ));
+        assert!(matches!(feed_all(&["1.", "e"]), FeedResult::Invalid(_)));
+        assert!(matches!(
+            feed_all(&["nul", "l ", "x"]),
+            FeedResult::Invalid(_)
+        ));
+        assert!(matches!(
+            feed_all(&["\"a", "b\""]),
+            FeedResult::Complete(JsonRootType::String)
+        ));
+    }
+
+    #[test]
+    fn test_parse_within_budget() {
+        let input = r#"{"name": "widget", "tags": ["a", "b"], "dims": {"w": [1, 2]}}"#;
+        let budget = Budget {
+            max_depth: 3,
+            max_nodes: 9,
+            max_bytes: input.len(),
+            max_string_len: 6,
+        };
+        assert!(JsonDocument::parse_within_budget(input, &budget).is_ok());
+
+        let violated = |budget: Budget| match JsonDocument::parse_within_budget(input, &budget) {
+            Err(JsonParseError::BudgetExceeded { limit, .. }) => limit,
+            other => panic!("expected a budget violation, got {:?}", other),
+        };
+        assert_eq!(
+            violated(Budget {
+                max_depth: 2,
+                ..budget
+            }),
+            "max_depth"
+        );
+        assert_eq!(
+            violated(Budget {
+                max_nodes: 8,
+                ..budget
+            }),
+            "max_nodes"
+        );
+        assert_eq!(
+            violated(Budget {
+                max_bytes: input.len() - 1,
+                ..budget
+            }),
+            "max_bytes"
+        );
+        assert_eq!(
+            violated(Budget {
+                max_string_len: 5,
+                ..budget
+            }),
+            "max_string_len"
+        );
+    }
+
+    #[test]
+    fn test_validate_fragment() {
+        assert!(JsonDocument::validate_fragment("-12.5e3", Rule::number));
+        assert!(JsonDocument::validate_fragment(r#""a\nb""#, Rule::string));
+        assert!(JsonDocument::validate_fragment("[1, 2]", Rule::array));
+        assert!(!JsonDocument::validate_fragment("12abc", Rule::number));
+        assert!(!JsonDocument::validate_fragment(" 1", Rule::number));
+        assert!(!JsonDocument::validate_fragment("01", Rule::number));
+        assert!(!JsonDocument::validate_fragment("[1, 2]", Rule::object));
+    }
+
+    #[test]
+    fn test_root_rule_name() {
+        assert_eq!(
+            JsonDocument::root_rule_name(r#" {"a": 1} "#).unwrap(),
+            "object"
+        );
+        assert_eq!(JsonDocument::root_rule_name("[1, 2]").unwrap(), "array");
+        assert_eq!(JsonDocument::root_rule_name("true").unwrap(), "boolean");
+        assert!(JsonDocument::root_rule_name("[1,").is_err());
+    }
+
+    #[test]
+    fn test_parse_trace() {
+        let trace = JsonDocument::parse_trace(r#"{"a": [1, null]}"#);
+        assert_eq!(
+            trace,
+            concat!(
+                "document 0..16\n",
+                "  object 0..16\n",
+                "    pair 1..15\n",
+                "      string 1..4\n",
+                "        inner 2..3 \"a\"\n",
+                "      array 6..15\n",
+                "        number 7..8 \"1\"\n",
+                "        null 10..14 \"null\"\n",
+                "  EOI 16..16 \"\"\n",
+            )
+        );
+        assert!(JsonDocument::parse_trace("[1,").contains("1:4"));
+    }
+
+    #[test]
+    fn test_root_type_display() {
+        assert_eq!(JsonRootType::Object.to_string(), "object");
+        assert_eq!(JsonRootType::Array.to_string(), "array");
+        assert_eq!(JsonRootType::String.to_string(), "string");
+        assert_eq!(JsonRootType::Number.to_string(), "number");
+        assert_eq!(JsonRootType::Boolean.to_string(), "boolean");
+        assert_eq!(JsonRootType::Null.to_string(), "null");
+    }
+
+    #[test]
+    fn test_is_valid_with_root() {
+        let object = r#"{"key": [1, 2]}"#;
+        let array = r#"[{"key": 1}]"#;
+
+        assert!(JsonDocument::is_valid_with_root(
+            object,
+            JsonRootType::Object
+        ));
+        assert!(!JsonDocument::is_valid_with_root(
+            object,
+            JsonRootType::Array
+        ));
+        assert!(JsonDocument::is_valid_with_root(array, JsonRootType::Array));
+        assert!(!JsonDocument::is_valid_with_root(
+            array,
+            JsonRootType::Object
+        ));
+
+        assert!(!JsonDocument::is_valid_with_root(
+            "{\"key\": }",
+            JsonRootType::Object
+        ));
+        assert!(JsonDocument::is_valid_with_root(
+            " -1.5 ",
+            JsonRootType::Number
+        ));
+        assert!(!JsonDocument::is_valid_with_root("", JsonRootType::Null));
+    }
+
+    #[test]
+    fn test_is_standard_json() {
+        assert!(JsonDocument::is_standard_json(r#"{"key": [1, 2]}"#));
+        assert!(!JsonDocument::is_standard_json(
+            "{\n  // comment\n  \"key\": 1\n}"
+        ));
+        assert!(!JsonDocument::is_standard_json("{'key': 1}"));
+        assert!(!JsonDocument::is_standard_json("[1, 2,]"));
+        assert!(JsonDocument::is_standard_json("42"));
+        assert!(JsonDocument::is_standard_json(" \"x\" "));
+        assert!(JsonDocument::is_standard_json("null"));
+        assert!(!JsonDocument::is_standard_json("0x10"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        let doc: JsonDocument = "[1, 2]".parse().unwrap();
+        assert_eq!(doc.root_type, JsonRootType::Array);
+        assert!("{".parse::<JsonDocument>().is_err());
     }
 
     #[test]
     fn test_is_valid() {
         let valid_json = r#"{"key": "value"}"#;
         let invalid_json = r#"{"key": "value",}"#;
-        let empty_json = r#"   "#;
+        let empty_json = r#""#;
+        let whitespace_json = r#"   "#;
 
         assert!(JsonDocument::is_valid(valid_json));
         assert!(!JsonDocument::is_valid(invalid_json));
         assert!(!JsonDocument::is_valid(empty_json));
+        assert!(!JsonDocument::is_valid(whitespace_json));
     }
 }
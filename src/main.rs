@@ -1,12 +1,221 @@
+use std::collections::HashMap;
 use std::env;
-use json_parser::JsonDocument;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use json_parser::{
+    IndentStyle, JsonDocument, JsonParseError, JsonValueType, ParseOptions, SerializeOptions,
+};
+
+// Whether output is wrapped in ANSI color codes; decided once at startup.
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+// Colors only a terminal, and never when `--no-color` is passed or the
+// `NO_COLOR` environment variable is set to anything non-empty.
+fn use_color(no_color_flag: bool) -> bool {
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color_flag && !no_color_env && io::stdout().is_terminal()
+}
+
+fn paint(text: &str, code: &str) -> String {
+    if COLOR.load(Ordering::Relaxed) {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn green(text: &str) -> String {
+    paint(text, "32")
+}
+
+fn red(text: &str) -> String {
+    paint(text, "31")
+}
 
 fn print_help() {
     println!("JSON Parser using Pest!");
     println!("Usage: cargo run <file_path>");
+    println!("       cat <file_path> | cargo run -");
+    println!("       cargo run fmt [--check] <file_path>");
+    println!("       cargo run <file_path> --stats");
     println!("Options:");
     println!("  --help    Show this help message");
     println!("  --credits Show project credits");
+    println!("  -         Read the document from stdin");
+    println!("  --stats   Print the document's shape instead of its content");
+    println!("  --no-color Disable colored output (also set by the NO_COLOR variable)");
+    println!("Commands:");
+    println!("  fmt       Reformat the file in place with 2-space indentation");
+    println!("  --check   With fmt, fail if the file is not formatted instead of rewriting it");
+}
+
+// Returns whether validation failed.
+fn validate(source: &str, input: &str) -> bool {
+    report(source, Some(input), JsonDocument::parse(input))
+}
+
+// `input` is the source text, when available, for showing where an error is.
+// Returns whether validation failed.
+fn report(source: &str, input: Option<&str>, result: Result<JsonDocument, JsonParseError>) -> bool {
+    let failed = result.is_err();
+    // Print the parse results
+    match result {
+        Ok(doc) => {
+            println!("{}", green("✅ JSON is valid!"));
+            println!("File: {}", source);
+            println!("Root Type: {:?}", doc.root_type);
+            println!("{}", doc.content);
+        }
+        Err(e @ JsonParseError::Io { .. }) => {
+            eprintln!("{}", e);
+        }
+        Err(e) => {
+            println!("{}", red("❌ JSON is invalid!"));
+            println!("File: {}", source);
+            println!("{}", red(&format!("Error: {}", e)));
+            if let Some(input) = input {
+                print_caret(input, &e);
+            }
+        }
+    }
+    failed
+}
+
+// Prints the source line containing the error with a caret under its column,
+// like rustc, highlighting the offending character. Tabs are expanded to four
+// spaces so the caret stays aligned.
+fn print_caret(input: &str, error: &JsonParseError) {
+    let Some((line, column)) = error.position() else {
+        return;
+    };
+    // Files are parsed without their byte order mark, so positions count from after it
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let Some(text) = input.lines().nth(line - 1) else {
+        return;
+    };
+
+    let mut rendered = String::new();
+    let mut caret = None;
+    let mut width = 0;
+    for (index, c) in text.chars().enumerate() {
+        let expanded = match c {
+            '\t' => "    ".to_string(),
+            c => c.to_string(),
+        };
+        if index + 1 == column {
+            caret = Some(width);
+            rendered.push_str(&red(&expanded));
+        } else {
+            rendered.push_str(&expanded);
+        }
+        width += expanded.chars().count();
+    }
+    let caret = caret.unwrap_or(width);
+
+    let gutter = line.to_string();
+    println!("{} | {}", gutter, rendered);
+    println!(
+        "{} | {}{}",
+        " ".repeat(gutter.len()),
+        " ".repeat(caret),
+        red("^")
+    );
+}
+
+// Prints one `name: value` line per statistic, in a fixed order.
+fn print_stats(doc: &JsonDocument, bytes: u64) -> Result<(), JsonParseError> {
+    let value = doc.to_value()?;
+    let mut counts = HashMap::new();
+    let mut keys = 0;
+    let _ = value.walk(|node| {
+        *counts.entry(node.value_type()).or_insert(0) += 1;
+        if node.value_type() == JsonValueType::Object {
+            keys += node.len();
+        }
+        std::ops::ControlFlow::Continue(())
+    });
+
+    println!("root: {}", doc.root_type);
+    println!("depth: {}", value.depth());
+    for (kind, label) in [
+        (JsonValueType::Object, "objects"),
+        (JsonValueType::Array, "arrays"),
+        (JsonValueType::String, "strings"),
+        (JsonValueType::Number, "numbers"),
+        (JsonValueType::Bool, "booleans"),
+        (JsonValueType::Null, "nulls"),
+    ] {
+        println!("{}: {}", label, counts.get(&kind).unwrap_or(&0));
+    }
+    println!("keys: {}", keys);
+    println!("bytes: {}", bytes);
+    Ok(())
+}
+
+// Exits with status 1 if stdin cannot be read or is not valid JSON.
+fn validate_stdin() {
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("Error reading stdin: {}", e);
+        process::exit(1);
+    }
+    if validate("<stdin>", &input) {
+        process::exit(1);
+    }
+}
+
+// Reformats a file in place, or with `check` only reports whether it is formatted.
+// The original is never modified unless the new content is fully written.
+fn format_file(file_path: &str, check: bool) -> Result<(), String> {
+    // Keep number tokens byte for byte. Strings are re-escaped, so `\u00e9`
+    // becomes `é` and `\/` becomes `/`, and a UTF-8 byte order mark is dropped.
+    let parse_options = ParseOptions {
+        preserve_number_text: true,
+        ..ParseOptions::default()
+    };
+    let value = JsonDocument::parse_file_with_options(file_path, &parse_options)
+        .and_then(|doc| doc.to_value_with_options(&parse_options))
+        .map_err(|e| match e {
+            JsonParseError::Io { .. } => e.to_string(),
+            e => format!("❌ {} is invalid, left unchanged: {}", file_path, e),
+        })?;
+    let original =
+        fs::read(file_path).map_err(|e| format!("Error reading file {}: {}", file_path, e))?;
+
+    let options = SerializeOptions {
+        indent_style: Some(IndentStyle::default()),
+        ..SerializeOptions::default()
+    };
+    let formatted = format!("{}\n", value.to_string_with(&options));
+    if formatted.as_bytes() == original {
+        return Ok(());
+    }
+    if check {
+        return Err(format!("{} is not formatted", file_path));
+    }
+    write_atomically(Path::new(file_path), &formatted)
+        .map_err(|e| format!("Error writing file {}: {}", file_path, e))
+}
+
+// Writes to a temporary file beside the target, then renames it over the target.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("json");
+    let temp = path.with_file_name(format!(".{}.{}.tmp", name, process::id()));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(&temp, fs::metadata(path)?.permissions())?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
 }
 
 fn print_credits() {
@@ -17,11 +226,18 @@ fn print_credits() {
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let no_color = args.iter().any(|arg| arg == "--no-color");
+    args.retain(|arg| arg != "--no-color");
+    COLOR.store(use_color(no_color), Ordering::Relaxed);
 
-    // If no arguments are passed, print the help message.
+    // If no arguments are passed, read piped input or print the help message.
     if args.len() == 1 {
-        print_help();
+        if io::stdin().is_terminal() {
+            print_help();
+        } else {
+            validate_stdin();
+        }
         return;
     }
 
@@ -32,32 +248,40 @@ fn main() {
         "--credits" => {
             print_credits();
         }
+        "-" => {
+            validate_stdin();
+        }
+        "fmt" => {
+            let check = args[2..].iter().any(|arg| arg == "--check");
+            let Some(file_path) = args[2..].iter().find(|arg| *arg != "--check") else {
+                print_help();
+                process::exit(2);
+            };
+            if let Err(message) = format_file(file_path, check) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
         _ => {
             // Handle file path
+            let stats = args[2..].iter().any(|arg| arg == "--stats");
             let file_path = &args[1];
-
-            // Read the file content
-            let input = match std::fs::read_to_string(file_path) {
-                Ok(content) => content.trim().to_string(),
-                Err(e) => {
-                    eprintln!("Error reading file {}: {}", file_path, e);
-                    return;
+            let result = JsonDocument::parse_file(file_path);
+            if let (true, Ok(doc)) = (stats, &result) {
+                let bytes = fs::metadata(file_path).map_or(0, |meta| meta.len());
+                if let Err(e) = print_stats(doc, bytes) {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
                 }
+                return;
+            }
+            // The source is only needed to point at an error
+            let input = match result {
+                Err(_) => fs::read_to_string(file_path).ok(),
+                Ok(_) => None,
             };
-
-            // Parse the JSON and print results
-            match JsonDocument::parse(&input) {
-                Ok(doc) => {
-                    println!("✅ JSON is valid!");
-                    println!("File: {}", file_path);
-                    println!("Root Type: {:?}", doc.root_type);
-                    println!("{}", doc.content);
-                }
-                Err(e) => {
-                    println!("❌ JSON is invalid!");
-                    println!("File: {}", file_path);
-                    println!("Error: {}", e);
-                }
+            if report(file_path, input.as_deref(), result) {
+                process::exit(1);
             }
         }
     }
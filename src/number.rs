@@ -0,0 +1,238 @@
+//! # JSON Numbers
+//!
+//! This module provides the number representation used by `JsonValue`, which
+//! keeps 64-bit integers exact instead of rounding them through `f64`.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Smallest magnitude at which an `f64` no longer holds every integer exactly (2^53).
+const MAX_EXACT_FLOAT: f64 = 9_007_199_254_740_992.0;
+
+/// A JSON number.
+///
+/// Integer literals are stored in the narrowest integer variant that holds them
+/// exactly; anything with a fraction or exponent, or too large for `u64`, is
+/// stored as `F64`. Literals beyond the range of `f64`, such as `1e400`, are
+/// kept as `Raw` source text instead of becoming infinity, as is every number
+/// parsed with `ParseOptions::preserve_number_text`.
+#[derive(Debug, Clone)]
+pub enum JsonNumber {
+    /// An integer that fits in `i64`
+    I64(i64),
+    /// A non-negative integer above `i64::MAX`
+    U64(u64),
+    /// Any other number, stored as a 64-bit float
+    F64(f64),
+    /// A number kept as its exact source text, written back out verbatim
+    Raw(String),
+}
+
+impl JsonNumber {
+    /// Builds a number from a token matched by the `number` rule.
+    ///
+    /// `-0` has no integer form, so it is kept as `F64(-0.0)` to preserve its sign.
+    /// Tokens that overflow `f64` are kept as `Raw`.
+    pub(crate) fn from_token(token: &str) -> Self {
+        if !token.contains(['.', 'e', 'E']) {
+            match token.parse::<i64>() {
+                Ok(0) if token.starts_with('-') => return JsonNumber::F64(-0.0),
+                Ok(n) => return JsonNumber::I64(n),
+                Err(_) => {}
+            }
+            if let Ok(n) = token.parse::<u64>() {
+                return JsonNumber::U64(n);
+            }
+        }
+        match token.parse::<f64>() {
+            Ok(n) if n.is_finite() => JsonNumber::F64(n),
+            _ => JsonNumber::Raw(token.to_string()),
+        }
+    }
+
+    /// Builds a number from a token matched by the `js_number` rule, mapping
+    /// `NaN` and the infinities to the corresponding `f64` values.
+    pub(crate) fn from_js_token(token: &str) -> Self {
+        match token {
+            "NaN" => JsonNumber::F64(f64::NAN),
+            "Infinity" => JsonNumber::F64(f64::INFINITY),
+            "-Infinity" => JsonNumber::F64(f64::NEG_INFINITY),
+            _ => JsonNumber::from_token(token.trim_start_matches('+')),
+        }
+    }
+
+    /// Returns true if this is a `Raw` number whose token overflows `f64`.
+    pub(crate) fn is_out_of_range(&self) -> bool {
+        match self {
+            JsonNumber::Raw(text) => !text.parse::<f64>().is_ok_and(f64::is_finite),
+            _ => false,
+        }
+    }
+
+    /// Returns the numeric form of a `Raw` number's text, or `None` for other
+    /// variants and for text that overflows `f64`.
+    pub(crate) fn parse_raw(&self) -> Option<JsonNumber> {
+        match self {
+            JsonNumber::Raw(text) => match JsonNumber::from_token(text) {
+                JsonNumber::Raw(_) => None,
+                number => Some(number),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `f64`, rounding large integers and saturating
+    /// out-of-range `Raw` numbers to infinity.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            JsonNumber::I64(n) => *n as f64,
+            JsonNumber::U64(n) => *n as f64,
+            JsonNumber::F64(n) => *n,
+            JsonNumber::Raw(text) => text.parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// Returns the value as an `i64` if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.exact_integer().and_then(|n| i64::try_from(n).ok())
+    }
+
+    /// Returns the value as a `u64` if it is a non-negative integer in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.exact_integer().and_then(|n| u64::try_from(n).ok())
+    }
+
+    /// Returns true if the value has no fractional part.
+    pub fn is_integer(&self) -> bool {
+        match self {
+            JsonNumber::I64(_) | JsonNumber::U64(_) => true,
+            JsonNumber::F64(n) => n.is_finite() && n % 1.0 == 0.0,
+            JsonNumber::Raw(_) => self.parse_raw().is_some_and(|n| n.is_integer()),
+        }
+    }
+
+    /// Returns the exact integer value, if this number is an integer that fits in `i128`.
+    fn exact_integer(&self) -> Option<i128> {
+        match *self {
+            JsonNumber::I64(n) => Some(i128::from(n)),
+            JsonNumber::U64(n) => Some(i128::from(n)),
+            JsonNumber::F64(n) if n % 1.0 == 0.0 && n.abs() < i128::MAX as f64 => Some(n as i128),
+            JsonNumber::F64(_) => None,
+            JsonNumber::Raw(_) => self.parse_raw().and_then(|n| n.exact_integer()),
+        }
+    }
+}
+
+/// Compares numbers by exact numeric value, regardless of variant.
+///
+/// `I64(1)` equals `F64(1.0)`, but `I64(9007199254740993)` does not equal
+/// `F64(9007199254740992.0)` even though both round to the same `f64`.
+impl PartialEq for JsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.exact_integer(), other.exact_integer()) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) if self.is_out_of_range() || other.is_out_of_range() => {
+                matches!((self, other), (JsonNumber::Raw(a), JsonNumber::Raw(b)) if a == b)
+            }
+            (None, None) => self.as_f64() == other.as_f64(),
+            _ => false,
+        }
+    }
+}
+
+/// Formats the number as JSON text; integers never have a decimal point.
+///
+/// Floats of magnitude 2^53 and above are written with an exponent, since
+/// their plain digits would read back as a different integer. `Raw` numbers
+/// are written as their source text. Non-finite floats, which can only be
+/// built directly, have no JSON spelling and are written as `null`.
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            JsonNumber::I64(n) => write!(f, "{}", n),
+            JsonNumber::U64(n) => write!(f, "{}", n),
+            JsonNumber::F64(n) if n.is_finite() && n.abs() >= MAX_EXACT_FLOAT => {
+                write!(f, "{:e}", n)
+            }
+            JsonNumber::F64(n) if n.is_finite() => write!(f, "{}", n),
+            JsonNumber::F64(_) => f.write_str("null"),
+            JsonNumber::Raw(ref text) => f.write_str(text),
+        }
+    }
+}
+
+impl From<i64> for JsonNumber {
+    fn from(n: i64) -> Self {
+        JsonNumber::I64(n)
+    }
+}
+
+impl From<u64> for JsonNumber {
+    fn from(n: u64) -> Self {
+        i64::try_from(n).map_or(JsonNumber::U64(n), JsonNumber::I64)
+    }
+}
+
+impl From<f64> for JsonNumber {
+    fn from(n: f64) -> Self {
+        JsonNumber::F64(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_narrowest_representation() {
+        assert!(matches!(
+            JsonNumber::from_token("-42"),
+            JsonNumber::I64(-42)
+        ));
+        assert!(matches!(
+            JsonNumber::from_token("9007199254740993"),
+            JsonNumber::I64(9007199254740993)
+        ));
+        assert!(matches!(
+            JsonNumber::from_token("18446744073709551615"),
+            JsonNumber::U64(u64::MAX)
+        ));
+        assert!(matches!(
+            JsonNumber::from_token("18446744073709551616"),
+            JsonNumber::F64(_)
+        ));
+        assert!(matches!(JsonNumber::from_token("1.0"), JsonNumber::F64(_)));
+        assert_eq!(JsonNumber::from_token("-0").to_string(), "-0");
+        assert!(matches!(JsonNumber::from_token("1e2"), JsonNumber::F64(_)));
+        assert!(matches!(
+            JsonNumber::from_token("-1e400"),
+            JsonNumber::Raw(_)
+        ));
+    }
+
+    #[test]
+    fn test_exact_equality() {
+        assert_eq!(JsonNumber::I64(1), JsonNumber::F64(1.0));
+        assert_eq!(JsonNumber::from(u64::MAX), JsonNumber::U64(u64::MAX));
+        assert_ne!(
+            JsonNumber::I64(9007199254740993),
+            JsonNumber::F64(9007199254740992.0)
+        );
+        assert_ne!(JsonNumber::I64(1), JsonNumber::F64(1.5));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            JsonNumber::I64(9007199254740993).to_string(),
+            "9007199254740993"
+        );
+        assert_eq!(
+            JsonNumber::U64(u64::MAX).to_string(),
+            "18446744073709551615"
+        );
+        assert_eq!(JsonNumber::F64(2.5).to_string(), "2.5");
+        assert_eq!(JsonNumber::F64(7.5e18).to_string(), "7.5e18");
+        assert_eq!(JsonNumber::F64(f64::INFINITY).to_string(), "null");
+    }
+}
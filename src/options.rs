@@ -0,0 +1,232 @@
+//! # Parse Options
+//!
+//! This module provides the settings accepted by `JsonDocument::parse_with_options`.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use pest::iterators::Pair;
+
+use crate::report::is_unsafe_integer;
+use crate::{JsonParseError, JsonRootType, ParseReport, ParseWarning, Rule};
+
+/// Options controlling how a document is parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Root types accepted by the parser; others yield `UnexpectedRootType`
+    pub allowed_roots: BTreeSet<JsonRootType>,
+    /// Rejects objects whose key density suggests a hash-flood payload
+    pub hash_flood_guard: Option<HashFloodGuard>,
+    /// Storage used for objects when building a `JsonValue`
+    pub object_backing: ObjectBacking,
+    /// How repeated keys within one object are resolved when building a `JsonValue`
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Reports integers beyond JavaScript's `Number.MAX_SAFE_INTEGER` as warnings
+    pub warn_unsafe_integers: bool,
+    /// Maximum number of values in the document, counting containers but not
+    /// keys. Parsing checks it while walking the grammar's token tree, which
+    /// pest has already built for the whole input, so it bounds the work done
+    /// after the grammar parse rather than the parse itself.
+    /// `JsonDocument::to_value_with_options` checks it as it builds each
+    /// value, stopping at the first value over the limit.
+    pub max_values: Option<usize>,
+    /// Accepts whitespace before and after the root value; when false, such
+    /// surrounding whitespace is a syntax error. Whitespace between tokens is
+    /// structural and always allowed.
+    pub trim_input: bool,
+    /// Fails with `NumberOutOfRange` when building a `JsonValue` from a number
+    /// too large for `f64`; when false, such numbers are kept as
+    /// `JsonNumber::Raw`
+    pub reject_overflowing_numbers: bool,
+    /// Stores every number in a `JsonValue` as `JsonNumber::Raw`, keeping its
+    /// exact source text so it is written back out byte for byte
+    pub preserve_number_text: bool,
+    /// Accepts `NaN`, `Infinity`, `-Infinity`, and numbers with a leading `+`,
+    /// as JavaScript and JSON5 producers emit. `NaN` and the infinities have
+    /// no JSON spelling, so they serialize as `null` and do not round-trip.
+    pub allow_js_numbers: bool,
+    /// Converts every string and object key in a `JsonValue` to Unicode
+    /// Normalization Form C, so a letter followed by a combining accent
+    /// equals its precomposed form
+    #[cfg(feature = "unicode")]
+    pub normalize_nfc: bool,
+}
+
+impl Default for ParseOptions {
+    /// Accepts object and array roots only, matching `JsonDocument::parse`,
+    /// trims surrounding whitespace, and disables every other check.
+    fn default() -> Self {
+        ParseOptions {
+            allowed_roots: BTreeSet::from([JsonRootType::Object, JsonRootType::Array]),
+            hash_flood_guard: None,
+            object_backing: ObjectBacking::default(),
+            duplicate_keys: DuplicateKeyPolicy::default(),
+            warn_unsafe_integers: false,
+            max_values: None,
+            trim_input: true,
+            reject_overflowing_numbers: false,
+            preserve_number_text: false,
+            allow_js_numbers: false,
+            #[cfg(feature = "unicode")]
+            normalize_nfc: false,
+        }
+    }
+}
+
+/// How objects are stored when building a `JsonValue` tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectBacking {
+    /// Members kept as ordered pairs in `JsonValue::Object`, preserving
+    /// source order and duplicate keys
+    #[default]
+    Ordered,
+    /// Members kept in a `JsonValue::Map`, giving O(log n) lookup at the
+    /// cost of source order; a duplicate key keeps its last value
+    Map,
+}
+
+/// How an object that repeats a key is turned into a `JsonValue`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Fails with `DuplicateKey` naming the first repeated key
+    Error,
+    /// Keeps the first member with each key and drops later ones
+    KeepFirst,
+    /// Keeps the value of the last member with each key, at the position of
+    /// the first
+    KeepLast,
+    /// Keeps every member as written; with `ObjectBacking::Map`, which cannot
+    /// hold repeated keys, this behaves like `KeepLast`
+    #[default]
+    KeepAll,
+}
+
+/// Thresholds for detecting objects crafted to flood hash maps.
+///
+/// An object is rejected when it has at least `min_keys` members and more
+/// than `max_keys_per_byte` members per byte of its source text. Legitimate
+/// objects with many members tend to carry real values, so their density is
+/// far lower than a payload of tiny keys mapped to tiny values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HashFloodGuard {
+    /// Objects with fewer members than this are never rejected
+    pub min_keys: usize,
+    /// Maximum number of members per byte of the object's source text
+    pub max_keys_per_byte: f64,
+}
+
+impl Default for HashFloodGuard {
+    fn default() -> Self {
+        HashFloodGuard {
+            min_keys: 1024,
+            max_keys_per_byte: 0.1,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns the default options with every root type allowed, as strict
+    /// RFC 8259 permits.
+    pub(crate) fn any_root() -> Self {
+        ParseOptions {
+            allowed_roots: BTreeSet::from([
+                JsonRootType::Object,
+                JsonRootType::Array,
+                JsonRootType::String,
+                JsonRootType::Number,
+                JsonRootType::Boolean,
+                JsonRootType::Null,
+            ]),
+            ..ParseOptions::default()
+        }
+    }
+
+    /// Builds the error for a root of a type outside `allowed_roots`.
+    pub(crate) fn unexpected_root(&self, root_type: JsonRootType) -> JsonParseError {
+        let mut allowed: Vec<String> = self.allowed_roots.iter().map(ToString::to_string).collect();
+        allowed.sort();
+        JsonParseError::UnexpectedRootType(format!(
+            "{} (allowed: {})",
+            root_type,
+            allowed.join(", ")
+        ))
+    }
+
+    /// Returns the grammar rule a whole document is parsed with.
+    pub(crate) fn root_rule(&self) -> Rule {
+        if self.allow_js_numbers {
+            Rule::lenient_json
+        } else {
+            Rule::document
+        }
+    }
+
+    /// Walks the parsed tree, checking it against the configured limits and
+    /// recording warnings in `report`, and returns the number of values.
+    pub(crate) fn enforce(
+        &self,
+        pair: &Pair<Rule>,
+        report: &mut ParseReport,
+    ) -> Result<usize, JsonParseError> {
+        let mut values = 0;
+        self.enforce_pair(pair, report, &mut values)?;
+        Ok(values)
+    }
+
+    fn enforce_pair(
+        &self,
+        pair: &Pair<Rule>,
+        report: &mut ParseReport,
+        values: &mut usize,
+    ) -> Result<(), JsonParseError> {
+        let is_value = matches!(
+            pair.as_rule(),
+            Rule::object
+                | Rule::array
+                | Rule::string
+                | Rule::number
+                | Rule::js_number
+                | Rule::boolean
+                | Rule::null
+        );
+        *values += usize::from(is_value);
+        if let (true, Some(limit)) = (is_value, self.max_values) {
+            if *values > limit {
+                return Err(JsonParseError::TooManyValues { limit });
+            }
+        }
+
+        if self.warn_unsafe_integers
+            && pair.as_rule() == Rule::number
+            && is_unsafe_integer(pair.as_str())
+        {
+            let (line, column) = pair.line_col();
+            report.warnings.push(ParseWarning::UnsafeInteger {
+                token: pair.as_str().to_string(),
+                line,
+                column,
+            });
+        }
+
+        if let (Rule::object, Some(guard)) = (pair.as_rule(), &self.hash_flood_guard) {
+            let keys = pair.clone().into_inner().count();
+            let bytes = pair.as_str().len();
+            if keys >= guard.min_keys && keys as f64 / bytes as f64 > guard.max_keys_per_byte {
+                return Err(JsonParseError::HashFlood {
+                    offset: pair.as_span().start(),
+                    keys,
+                    bytes,
+                });
+            }
+        }
+
+        // The key of an object member is not a value
+        let skip = usize::from(pair.as_rule() == Rule::pair);
+        for child in pair.clone().into_inner().skip(skip) {
+            self.enforce_pair(&child, report, values)?;
+        }
+        Ok(())
+    }
+}
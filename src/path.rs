@@ -0,0 +1,241 @@
+//! # Wildcard Paths
+//!
+//! This module resolves JSON Pointers extended with a `*` wildcard that
+//! matches every element of an array or every member of an object, such as
+//! `/users/*/age`, and a dotted selector syntax such as `users[*].age`.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use thiserror::Error;
+
+use crate::value::{array_index, unescape_pointer_token};
+use crate::JsonValue;
+
+/// Error for a wildcard path that cannot be resolved.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The path is neither empty nor starts with `/`.
+    #[error("Malformed path '{0}': paths must be empty or start with '/'")]
+    Malformed(String),
+    /// The path matched no values.
+    #[error("No values match path '{0}'")]
+    NoMatch(String),
+}
+
+impl JsonValue {
+    /// Returns every value matched by a wildcard path, in document order.
+    ///
+    /// The path is a JSON Pointer (RFC 6901) in which a `*` token matches all
+    /// elements of an array or all members of an object. Because of this, a
+    /// member whose key is literally `*` cannot be addressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Malformed` for a path not starting with `/`, or `NoMatch` if no
+    /// value matches.
+    pub fn query(&self, path: &str) -> Result<Vec<&JsonValue>, PathError> {
+        let mut current = vec![self];
+        if !path.is_empty() {
+            let tokens = path
+                .strip_prefix('/')
+                .ok_or_else(|| PathError::Malformed(path.to_string()))?;
+            for token in tokens.split('/') {
+                current = current
+                    .into_iter()
+                    .flat_map(|value| step(value, token))
+                    .collect();
+            }
+        }
+
+        if current.is_empty() {
+            Err(PathError::NoMatch(path.to_string()))
+        } else {
+            Ok(current)
+        }
+    }
+
+    /// Returns every value matched by a dotted selector, in document order.
+    ///
+    /// A selector is a chain of steps: `name` or `.name` selects an object
+    /// member, `[2]` an array element, and `[*]` every element of an array or
+    /// every member of an object. So `users[*].name` selects the name of each
+    /// user, and the empty selector selects this value.
+    ///
+    /// Keys cannot contain `.` or `[`; use `pointer` or `query` for those.
+    /// A malformed selector, such as `a..b` or `a[x]`, matches nothing, so an
+    /// empty result means either no match or a bad selector.
+    pub fn select(&self, selector: &str) -> Vec<&JsonValue> {
+        let Some(steps) = parse_selector(selector) else {
+            return Vec::new();
+        };
+        steps.iter().fold(vec![self], |current, step| {
+            current
+                .into_iter()
+                .flat_map(|value| select_step(value, step))
+                .collect()
+        })
+    }
+
+    /// Checks whether every value matched by a wildcard path has the same type.
+    ///
+    /// Types are compared with `value_type`, so integers and fractions are both
+    /// numbers. Useful for catching schema drift, such as a field that holds
+    /// numbers in most records but strings in others.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PathError` under the same conditions as `query`.
+    pub fn values_homogeneous_at(&self, path: &str) -> Result<bool, PathError> {
+        let values = self.query(path)?;
+        let first = values[0].value_type();
+        Ok(values.iter().all(|value| value.value_type() == first))
+    }
+}
+
+/// Resolves one path token against a value.
+fn step<'a>(value: &'a JsonValue, token: &str) -> Vec<&'a JsonValue> {
+    match (value, token) {
+        (JsonValue::Array(items), "*") => items.iter().collect(),
+        (JsonValue::Object(_) | JsonValue::Map(_), "*") => value
+            .members()
+            .into_iter()
+            .flatten()
+            .map(|(_, v)| v)
+            .collect(),
+        (JsonValue::Array(items), index) => array_index(index)
+            .and_then(|index| items.get(index))
+            .into_iter()
+            .collect(),
+        (_, key) => value
+            .get(&unescape_pointer_token(key))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// One step of a `select` selector.
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+    Wildcard,
+}
+
+/// Splits a selector into steps, or returns `None` if it is malformed.
+fn parse_selector(selector: &str) -> Option<Vec<Step<'_>>> {
+    let mut steps = Vec::new();
+    let mut rest = selector;
+    while !rest.is_empty() {
+        if let Some(bracketed) = rest.strip_prefix('[') {
+            let (inner, tail) = bracketed.split_once(']')?;
+            steps.push(match inner {
+                "*" => Step::Wildcard,
+                index if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => {
+                    Step::Index(index.parse().ok()?)
+                }
+                _ => return None,
+            });
+            rest = tail;
+        } else {
+            // Only the first step may omit the leading dot
+            let key = if steps.is_empty() {
+                rest
+            } else {
+                rest.strip_prefix('.')?
+            };
+            let end = key.find(['.', '[']).unwrap_or(key.len());
+            if end == 0 {
+                return None;
+            }
+            steps.push(Step::Key(&key[..end]));
+            rest = &key[end..];
+        }
+    }
+    Some(steps)
+}
+
+/// Resolves one selector step against a value.
+fn select_step<'a>(value: &'a JsonValue, step: &Step<'_>) -> Vec<&'a JsonValue> {
+    match (value, step) {
+        (JsonValue::Array(items), Step::Wildcard) => items.iter().collect(),
+        (_, Step::Wildcard) => value.entries().map(|(_, v)| v).collect(),
+        (JsonValue::Array(items), Step::Index(index)) => items.get(*index).into_iter().collect(),
+        (_, Step::Key(key)) => value.get(key).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonDocument;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    #[test]
+    fn test_query_wildcard() {
+        let v = value(r#"{"users": [{"name": "ann"}, {"name": "bob"}, {"id": 3}]}"#);
+        let names = v.query("/users/*/name").unwrap();
+        assert_eq!(
+            names,
+            [
+                &JsonValue::String("ann".to_string()),
+                &JsonValue::String("bob".to_string())
+            ]
+        );
+        assert_eq!(
+            v.query("users"),
+            Err(PathError::Malformed("users".to_string()))
+        );
+        assert_eq!(
+            v.query("/users/*/email"),
+            Err(PathError::NoMatch("/users/*/email".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let v = value(
+            r#"{"users": [{"name": "ann", "tags": ["a", "b"]}, {"name": "bob", "tags": []}, {"id": 3}],
+                "meta": {"x": 1, "y": 2}}"#,
+        );
+        let strings = |values: Vec<&JsonValue>| -> Vec<String> {
+            values.iter().map(|value| value.to_string()).collect()
+        };
+
+        assert_eq!(strings(v.select("users[*].name")), [r#""ann""#, r#""bob""#]);
+        assert_eq!(strings(v.select("users[0].tags[1]")), [r#""b""#]);
+        assert_eq!(strings(v.select("users[*].tags[*]")), [r#""a""#, r#""b""#]);
+        assert_eq!(strings(v.select("meta[*]")), ["1", "2"]);
+        assert_eq!(v.select(""), [&v]);
+        assert_eq!(value("[[1], [2]]").select("[*][0]").len(), 2);
+
+        assert!(v.select("users[3]").is_empty());
+        assert!(v.select("meta.x.y").is_empty());
+        for malformed in [
+            "users..name",
+            ".users",
+            "users[",
+            "users[x]",
+            "users[+1]",
+            "users[0]name",
+            "users.",
+        ] {
+            assert!(v.select(malformed).is_empty(), "{}", malformed);
+        }
+    }
+
+    #[test]
+    fn test_values_homogeneous_at() {
+        let v = value(
+            r#"{"rows": [{"age": 30}, {"age": 41.5}, {"age": "unknown"}], "meta": {"a": 1, "b": 2}}"#,
+        );
+        assert!(!v.values_homogeneous_at("/rows/*/age").unwrap());
+        assert!(v.values_homogeneous_at("/rows/0/age").unwrap());
+        assert!(v.values_homogeneous_at("/meta/*").unwrap());
+        assert!(v.values_homogeneous_at("/rows/*/missing").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! # Parse Reports
+//!
+//! This module provides the non-fatal findings and metrics collected while
+//! parsing.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+/// Non-fatal findings collected while parsing a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Warnings in the order they were found
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Size and timing of a successful parse, from `JsonDocument::parse_with_metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// Length of the input in bytes, including surrounding whitespace
+    pub input_bytes: usize,
+    /// Time spent parsing and walking the tree, excluding copying the input
+    pub parse_duration: Duration,
+    /// Number of values in the document, counting containers but not keys
+    pub value_count: usize,
+}
+
+/// A single non-fatal finding about a valid document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// An integer outside JavaScript's safe range (±2^53 - 1), which loses
+    /// precision when read as a JavaScript `Number`
+    UnsafeInteger {
+        token: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::UnsafeInteger {
+                token,
+                line,
+                column,
+            } => write!(
+                f,
+                "Integer {} at line {}, column {} exceeds JavaScript's safe integer range",
+                token, line, column
+            ),
+        }
+    }
+}
+
+/// Largest integer a JavaScript `Number` represents exactly (`Number.MAX_SAFE_INTEGER`).
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Returns true if `token` is an integer literal beyond `MAX_SAFE_INTEGER` in magnitude.
+pub(crate) fn is_unsafe_integer(token: &str) -> bool {
+    if token.contains(['.', 'e', 'E']) {
+        return false;
+    }
+    let digits = token.trim_start_matches('-');
+    digits.parse::<u64>().map_or(true, |n| n > MAX_SAFE_INTEGER)
+}
@@ -0,0 +1,337 @@
+//! # JSON Schema Subset
+//!
+//! This module validates values against a minimal subset of JSON Schema,
+//! supporting the `type`, `required`, `properties`, and `items` keywords.
+//! `items` accepts either a single schema for every element or an array of
+//! schemas matched to elements by position.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::value::escape_pointer_token;
+use crate::JsonValue;
+
+/// A compiled schema built from a JSON value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonSchema {
+    /// Allowed type names, or `None` when any type is accepted
+    types: Option<Vec<String>>,
+    /// Keys that must be present when the instance is an object
+    required: Vec<String>,
+    /// Schemas applied to the matching members of an object
+    properties: Vec<(String, JsonSchema)>,
+    /// Schemas applied to the elements of an array
+    items: Option<Items>,
+}
+
+/// The two forms of the `items` keyword.
+#[derive(Debug, Clone, PartialEq)]
+enum Items {
+    /// A single schema applied to every element
+    All(Box<JsonSchema>),
+    /// One schema per position; elements beyond the list are not checked
+    Tuple(Vec<JsonSchema>),
+}
+
+/// A single validation failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON Pointer to the offending value
+    pub path: String,
+    /// Human-readable description of the failure
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "at root: {}", self.message)
+        } else {
+            write!(f, "at {}: {}", self.path, self.message)
+        }
+    }
+}
+
+const TYPE_NAMES: [&str; 7] = [
+    "null", "boolean", "number", "integer", "string", "array", "object",
+];
+
+impl JsonSchema {
+    /// Builds a schema from its JSON representation.
+    ///
+    /// Unknown keywords are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SchemaError` pointing into the schema if a supported keyword
+    /// has a malformed value.
+    pub fn from_value(schema: &JsonValue) -> Result<Self, SchemaError> {
+        Self::compile(schema, String::new())
+    }
+
+    fn compile(schema: &JsonValue, path: String) -> Result<Self, SchemaError> {
+        let Some(members) = schema.members() else {
+            return Err(SchemaError {
+                path,
+                message: "schema must be an object".to_string(),
+            });
+        };
+
+        let mut compiled = JsonSchema::default();
+        for (keyword, value) in members {
+            let keyword_path = format!("{}/{}", path, escape_pointer_token(keyword));
+            match keyword {
+                "type" => {
+                    let names = match value {
+                        JsonValue::String(name) => vec![name.clone()],
+                        JsonValue::Array(items) => items
+                            .iter()
+                            .map(|item| match item {
+                                JsonValue::String(name) => Ok(name.clone()),
+                                _ => Err(invalid(&keyword_path, "type names must be strings")),
+                            })
+                            .collect::<Result<_, _>>()?,
+                        _ => return Err(invalid(&keyword_path, "type must be a string or array")),
+                    };
+                    if let Some(name) = names.iter().find(|n| !TYPE_NAMES.contains(&n.as_str())) {
+                        return Err(invalid(
+                            &keyword_path,
+                            &format!("unknown type name '{}'", name),
+                        ));
+                    }
+                    compiled.types = Some(names);
+                }
+                "required" => {
+                    let JsonValue::Array(items) = value else {
+                        return Err(invalid(&keyword_path, "required must be an array"));
+                    };
+                    for item in items {
+                        match item {
+                            JsonValue::String(key) => compiled.required.push(key.clone()),
+                            _ => {
+                                return Err(invalid(&keyword_path, "required keys must be strings"))
+                            }
+                        }
+                    }
+                }
+                "properties" => {
+                    let Some(props) = value.members() else {
+                        return Err(invalid(&keyword_path, "properties must be an object"));
+                    };
+                    for (key, sub) in props {
+                        let sub_path = format!("{}/{}", keyword_path, escape_pointer_token(key));
+                        compiled
+                            .properties
+                            .push((key.to_string(), Self::compile(sub, sub_path)?));
+                    }
+                }
+                "items" => {
+                    compiled.items = Some(match value {
+                        JsonValue::Array(schemas) => Items::Tuple(
+                            schemas
+                                .iter()
+                                .enumerate()
+                                .map(|(i, sub)| {
+                                    Self::compile(sub, format!("{}/{}", keyword_path, i))
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        _ => Items::All(Box::new(Self::compile(value, keyword_path)?)),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(compiled)
+    }
+
+    /// Validates a value against this schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every violation found, each with a JSON Pointer to the offending value.
+    pub fn validate(&self, doc: &JsonValue) -> Result<(), Vec<SchemaError>> {
+        let mut errors = Vec::new();
+        self.check(doc, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check(&self, value: &JsonValue, path: &str, errors: &mut Vec<SchemaError>) {
+        if let Some(types) = &self.types {
+            if !types.iter().any(|name| matches_type(value, name)) {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!(
+                        "expected {}, found {}",
+                        types.join(" or "),
+                        value.type_name()
+                    ),
+                });
+                return;
+            }
+        }
+
+        match value {
+            JsonValue::Object(_) | JsonValue::Map(_) => {
+                for key in &self.required {
+                    if value.get(key).is_none() {
+                        errors.push(SchemaError {
+                            path: path.to_string(),
+                            message: format!("missing required key '{}'", key),
+                        });
+                    }
+                }
+                for (key, schema) in &self.properties {
+                    let matching = value
+                        .members()
+                        .into_iter()
+                        .flatten()
+                        .filter(|(k, _)| k == key);
+                    for (_, member) in matching {
+                        let member_path = format!("{}/{}", path, escape_pointer_token(key));
+                        schema.check(member, &member_path, errors);
+                    }
+                }
+            }
+            JsonValue::Array(items) => match &self.items {
+                Some(Items::All(schema)) => {
+                    for (index, item) in items.iter().enumerate() {
+                        schema.check(item, &format!("{}/{}", path, index), errors);
+                    }
+                }
+                Some(Items::Tuple(schemas)) => {
+                    for (index, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                        schema.check(item, &format!("{}/{}", path, index), errors);
+                    }
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn invalid(path: &str, message: &str) -> SchemaError {
+    SchemaError {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn matches_type(value: &JsonValue, name: &str) -> bool {
+    match (name, value) {
+        ("integer", JsonValue::Number(n)) => n.is_integer(),
+        _ => value.type_name() == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonDocument;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    fn schema() -> JsonSchema {
+        JsonSchema::from_value(&value(
+            r#"{
+                "type": "object",
+                "required": ["name", "ports"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "ports": {"type": "array", "items": {"type": "integer"}}
+                }
+            }"#,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let doc = value(r#"{"name": "web", "ports": [80, 443]}"#);
+        assert!(schema().validate(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_type_mismatch_and_missing_key() {
+        let doc = value(r#"{"name": 42, "ports": [80, "443"]}"#);
+        let errors = schema().validate(&doc).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                SchemaError {
+                    path: "/name".to_string(),
+                    message: "expected string, found number".to_string(),
+                },
+                SchemaError {
+                    path: "/ports/1".to_string(),
+                    message: "expected integer, found string".to_string(),
+                },
+            ]
+        );
+
+        let errors = schema().validate(&value(r#"{"name": "web"}"#)).unwrap_err();
+        assert_eq!(errors[0].message, "missing required key 'ports'");
+    }
+
+    #[test]
+    fn test_homogeneous_items() {
+        let schema = JsonSchema::from_value(&value(
+            r#"{"type": "array", "items": {"type": "object", "required": ["id"]}}"#,
+        ))
+        .unwrap();
+
+        assert!(schema.validate(&value(r#"[{"id": 1}, {"id": 2}]"#)).is_ok());
+
+        let errors = schema
+            .validate(&value(r#"[{"id": 1}, {"name": "x"}, 3]"#))
+            .unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["/1", "/2"]);
+        assert_eq!(errors[1].message, "expected object, found number");
+    }
+
+    #[test]
+    fn test_tuple_items() {
+        let schema = JsonSchema::from_value(&value(
+            r#"{"items": [{"type": "string"}, {"type": "number"}, {"type": "boolean"}]}"#,
+        ))
+        .unwrap();
+
+        assert!(schema
+            .validate(&value(r#"["x", 1, true, "extra"]"#))
+            .is_ok());
+        assert!(schema.validate(&value(r#"["x"]"#)).is_ok());
+
+        let errors = schema.validate(&value(r#"[1, 2, "no"]"#)).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                SchemaError {
+                    path: "/0".to_string(),
+                    message: "expected string, found number".to_string(),
+                },
+                SchemaError {
+                    path: "/2".to_string(),
+                    message: "expected boolean, found string".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        let err = JsonSchema::from_value(&value(r#"{"type": "text"}"#)).unwrap_err();
+        assert_eq!(err.path, "/type");
+    }
+}
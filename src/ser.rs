@@ -0,0 +1,317 @@
+//! # Serialization
+//!
+//! This module writes `JsonValue` trees back out as JSON text.
+
+use alloc::string::String;
+use core::fmt::{self, Write};
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::JsonValue;
+
+/// Options controlling how `JsonValue::to_string_with` writes JSON text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// Escapes every character above U+007F as `\uXXXX`, using a surrogate
+    /// pair for characters outside the Basic Multilingual Plane
+    pub ascii_only: bool,
+    /// Spaces per nesting level for pretty output, or `None` for compact
+    /// output on a single line
+    pub indent: Option<usize>,
+    /// Indentation per nesting level for pretty output; when set, it takes
+    /// precedence over `indent`
+    pub indent_style: Option<IndentStyle>,
+    /// Line ending used between lines of pretty output, such as `"\r\n"`
+    pub newline: &'static str,
+    /// Writes `/` as `\/`, so output embedded in an HTML `<script>` element
+    /// cannot contain `</`
+    pub escape_forward_slash: bool,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            ascii_only: false,
+            indent: None,
+            indent_style: None,
+            newline: "\n",
+            escape_forward_slash: false,
+        }
+    }
+}
+
+impl SerializeOptions {
+    /// Returns the indentation for pretty output, or `None` for compact output.
+    fn pretty(&self) -> Option<IndentStyle> {
+        self.indent_style.or(self.indent.map(IndentStyle::Spaces))
+    }
+}
+
+/// The indentation written for each nesting level of pretty output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// This many spaces per level
+    Spaces(usize),
+    /// One tab per level
+    Tabs,
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        IndentStyle::Spaces(2)
+    }
+}
+
+impl JsonValue {
+    /// Serializes this value as JSON text configured by `options`.
+    ///
+    /// `to_string` is the same as calling this with default options.
+    pub fn to_string_with(&self, options: &SerializeOptions) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail
+        let _ = write_value(&mut out, self, options, 0);
+        out
+    }
+
+    /// Serializes this value as JSON text straight into `writer`, without
+    /// building the whole text in memory first.
+    ///
+    /// The text is written in many small pieces, so unbuffered sinks such as
+    /// a `File` or `TcpStream` should be wrapped in a `BufWriter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by `writer`.
+    #[cfg(feature = "std")]
+    pub fn to_writer<W: io::Write>(&self, writer: W, options: &SerializeOptions) -> io::Result<()> {
+        let mut adapter = IoWriter {
+            inner: writer,
+            error: None,
+        };
+        write_value(&mut adapter, self, options, 0).map_err(|_| {
+            adapter
+                .error
+                .unwrap_or_else(|| io::Error::other("formatting failed"))
+        })
+    }
+}
+
+/// Adapts an `io::Write` to `fmt::Write`, keeping the underlying I/O error
+/// that `fmt::Error` cannot carry.
+#[cfg(feature = "std")]
+struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Writes `value` as JSON text, with `level` giving the current nesting depth.
+pub(crate) fn write_value<W: Write>(
+    out: &mut W,
+    value: &JsonValue,
+    options: &SerializeOptions,
+    level: usize,
+) -> fmt::Result {
+    match value {
+        JsonValue::Null => out.write_str("null"),
+        JsonValue::Bool(b) => write!(out, "{}", b),
+        JsonValue::Number(n) => write!(out, "{}", n),
+        JsonValue::String(s) => write_string(out, s, options),
+        JsonValue::Array(items) => {
+            if items.is_empty() {
+                return out.write_str("[]");
+            }
+            out.write_char('[')?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_newline(out, options, level + 1)?;
+                write_value(out, item, options, level + 1)?;
+            }
+            write_newline(out, options, level)?;
+            out.write_char(']')
+        }
+        JsonValue::Object(_) | JsonValue::Map(_) => {
+            let mut members = value.members().into_iter().flatten().peekable();
+            if members.peek().is_none() {
+                return out.write_str("{}");
+            }
+            out.write_char('{')?;
+            for (index, (key, member)) in members.enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_newline(out, options, level + 1)?;
+                write_string(out, key, options)?;
+                out.write_str(if options.pretty().is_some() {
+                    ": "
+                } else {
+                    ":"
+                })?;
+                write_value(out, member, options, level + 1)?;
+            }
+            write_newline(out, options, level)?;
+            out.write_char('}')
+        }
+    }
+}
+
+/// Starts a new line indented to `level` when pretty-printing.
+fn write_newline<W: Write>(out: &mut W, options: &SerializeOptions, level: usize) -> fmt::Result {
+    let (unit, width) = match options.pretty() {
+        Some(IndentStyle::Spaces(width)) => (' ', width),
+        Some(IndentStyle::Tabs) => ('\t', 1),
+        None => return Ok(()),
+    };
+    out.write_str(options.newline)?;
+    (0..width * level).try_for_each(|_| out.write_char(unit))
+}
+
+/// Writes `s` as a quoted JSON string, escaping quotes, backslashes, and
+/// control characters, plus non-ASCII characters when `ascii_only` is set
+/// and `/` when `escape_forward_slash` is set.
+fn write_string<W: Write>(out: &mut W, s: &str, options: &SerializeOptions) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '/' if options.escape_forward_slash => out.write_str("\\/")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{8}' => out.write_str("\\b")?,
+            '\u{c}' => out.write_str("\\f")?,
+            c if c < ' ' || (options.ascii_only && !c.is_ascii()) => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            }
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::JsonDocument;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    #[test]
+    fn test_ascii_only() {
+        let v = value(r#"["café", "😀"]"#);
+        let ascii = SerializeOptions {
+            ascii_only: true,
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(
+            v.to_string_with(&SerializeOptions::default()),
+            r#"["café","😀"]"#
+        );
+        assert_eq!(v.to_string_with(&ascii), r#"["caf\u00e9","\ud83d\ude00"]"#);
+        assert_eq!(value(&v.to_string_with(&ascii)), v);
+    }
+
+    #[test]
+    fn test_forward_slash() {
+        let v = value(r#"["a\/b", "</script>"]"#);
+        let escaped = SerializeOptions {
+            escape_forward_slash: true,
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(v.to_string(), r#"["a/b","</script>"]"#);
+        assert_eq!(v.to_string_with(&escaped), r#"["a\/b","<\/script>"]"#);
+        assert_eq!(value(&v.to_string_with(&escaped)), v);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_writer() {
+        let v = value(r#"{"a": [1, 2.5, "x\ny"], "b": {"c": null}}"#);
+        let pretty = SerializeOptions {
+            indent_style: Some(IndentStyle::Tabs),
+            ..SerializeOptions::default()
+        };
+
+        let mut out = Vec::new();
+        v.to_writer(&mut out, &SerializeOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), v.to_string());
+
+        let mut out = Vec::new();
+        v.to_writer(&mut out, &pretty).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), v.to_string_with(&pretty));
+
+        let mut full = [0u8; 8];
+        let error = v
+            .to_writer(&mut full[..], &SerializeOptions::default())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_indent() {
+        let v = value(r#"{"a": [1, {"b": null}], "c": {}, "d": []}"#);
+        let pretty = SerializeOptions {
+            indent: Some(2),
+            ..SerializeOptions::default()
+        };
+        let styled = SerializeOptions {
+            indent_style: Some(IndentStyle::default()),
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(
+            v.to_string_with(&pretty),
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": {},\n  \"d\": []\n}"
+        );
+        assert_eq!(v.to_string_with(&styled), v.to_string_with(&pretty));
+        assert_eq!(
+            v.to_string_with(&SerializeOptions::default()),
+            v.to_string()
+        );
+    }
+
+    #[test]
+    fn test_tabs_and_crlf() {
+        let v = value(r#"{"a": [1, {}], "b": null}"#);
+        let tabs = SerializeOptions {
+            indent: Some(4),
+            indent_style: Some(IndentStyle::Tabs),
+            ..SerializeOptions::default()
+        };
+        let crlf = SerializeOptions {
+            indent_style: Some(IndentStyle::Spaces(1)),
+            newline: "\r\n",
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(
+            v.to_string_with(&tabs),
+            "{\n\t\"a\": [\n\t\t1,\n\t\t{}\n\t],\n\t\"b\": null\n}"
+        );
+        assert_eq!(
+            v.to_string_with(&crlf),
+            "{\r\n \"a\": [\r\n  1,\r\n  {}\r\n ],\r\n \"b\": null\r\n}"
+        );
+        assert_eq!(value(&v.to_string_with(&crlf)), v);
+    }
+}
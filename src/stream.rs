@@ -0,0 +1,310 @@
+//! # Streaming Scanners
+//!
+//! This module provides byte-level scanners that inspect JSON structure
+//! without building a parse tree.
+
+use crate::{
+    ErrorCategory, JsonDocument, JsonParseError, JsonRootType, ParseOptions, Rule, UTF8_BOM,
+};
+
+/// Byte-level scanner state for `JsonDocument::count_array_elements_streaming`.
+#[derive(Default)]
+pub(crate) struct ArrayCounter {
+    /// Bytes seen so far after any byte order mark, to tell zero-length input
+    /// from blank input
+    bytes: usize,
+    /// Bytes of a leading UTF-8 byte order mark skipped so far
+    bom: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    started: bool,
+    finished: bool,
+    expecting_element: bool,
+    count: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ArrayCounter {
+    pub(crate) fn feed(&mut self, byte: u8) -> Result<(), JsonParseError> {
+        if self.bytes == 0 && UTF8_BOM.get(self.bom) == Some(&byte) {
+            self.bom += 1;
+            return Ok(());
+        }
+        self.bytes += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else if byte & 0xC0 != 0x80 {
+            // Count characters, not UTF-8 continuation bytes
+            self.column += 1;
+        }
+
+        if self.in_string {
+            match byte {
+                _ if self.escaped => self.escaped = false,
+                b'\\' => self.escaped = true,
+                b'"' => self.in_string = false,
+                _ => {}
+            }
+            return Ok(());
+        }
+        if byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+        if self.finished {
+            return Err(self.syntax("unexpected content after the root array"));
+        }
+        if !self.started {
+            return match byte {
+                b'[' => {
+                    self.started = true;
+                    self.depth = 1;
+                    self.expecting_element = true;
+                    Ok(())
+                }
+                b'{' => Err(JsonParseError::UnexpectedRootType(
+                    JsonRootType::Object.to_string(),
+                )),
+                other => Err(JsonParseError::UnexpectedRootType(
+                    (other as char).to_string(),
+                )),
+            };
+        }
+
+        if self.depth == 1 && self.expecting_element && byte != b']' && byte != b',' {
+            self.count += 1;
+            self.expecting_element = false;
+        }
+        match byte {
+            b'"' => self.in_string = true,
+            b'[' | b'{' => self.depth += 1,
+            b']' | b'}' => {
+                self.depth -= 1;
+                self.finished = self.depth == 0;
+            }
+            b',' if self.depth == 1 => self.expecting_element = true,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<usize, JsonParseError> {
+        match (self.bytes, self.started, self.finished) {
+            (0, _, _) => Err(JsonParseError::EmptyJson),
+            (_, false, _) => Err(JsonParseError::WhitespaceOnly),
+            (_, true, false) => Err(self.syntax("unexpected end of input inside the root array")),
+            _ => Ok(self.count),
+        }
+    }
+
+    fn syntax(&self, message: &str) -> JsonParseError {
+        JsonParseError::Syntax {
+            message: message.to_string(),
+            line: self.line + 1,
+            column: self.column,
+            expected: Vec::new(),
+        }
+    }
+}
+
+/// The verdict of `StreamValidator::feed` on the input received so far.
+#[derive(Debug)]
+pub enum FeedResult {
+    /// The input is blank or a truncated but plausible JSON value
+    NeedMore,
+    /// The input is one complete, valid JSON value of this type
+    Complete(JsonRootType),
+    /// The input cannot become valid JSON whatever follows
+    Invalid(JsonParseError),
+}
+
+/// Validates a JSON document that arrives in chunks, such as buffered socket
+/// reads.
+///
+/// Chunks are scanned once for brackets and strings, and the full parser only
+/// runs when the root value closes or the brackets cannot match. Errors inside
+/// an open container, such as a missing colon, are therefore reported when the
+/// container closes. Root types are checked against the validator's
+/// `ParseOptions`, which by default allow only objects and arrays. An allowed
+/// scalar root is `Complete` as soon as it is valid, even though a number such
+/// as `12` could still grow.
+///
+/// # Examples
+///
+/// ```
+/// use json_parser::{FeedResult, JsonRootType, StreamValidator};
+///
+/// let mut validator = StreamValidator::new();
+/// assert!(matches!(validator.feed(r#"{"id": "#), FeedResult::NeedMore));
+/// assert!(matches!(
+///     validator.feed("7}"),
+///     FeedResult::Complete(JsonRootType::Object)
+/// ));
+/// ```
+#[derive(Debug, Default)]
+pub struct StreamValidator {
+    options: ParseOptions,
+    buffer: String,
+    /// Brackets still open, innermost last
+    open: Vec<u8>,
+    in_string: bool,
+    escaped: bool,
+    /// The first non-whitespace byte of the root value, once seen
+    root: Option<u8>,
+    /// Whether a root container or string has closed
+    closed: bool,
+    /// The verdict for `buffer` when it last held a complete value
+    complete: Option<JsonRootType>,
+    /// Whether the brackets can no longer match
+    broken: bool,
+}
+
+impl StreamValidator {
+    /// Creates a validator that has received no input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a validator that checks the input against `options`.
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Appends `chunk` and reports whether the input so far is complete,
+    /// incomplete, or invalid.
+    ///
+    /// A root of a type outside the options' `allowed_roots` is `Invalid` as
+    /// soon as its first byte arrives. Once the input is `Invalid` it stays so
+    /// for every later chunk.
+    pub fn feed(&mut self, chunk: &str) -> FeedResult {
+        self.buffer.push_str(chunk);
+        let mut changed = false;
+        for &byte in chunk.as_bytes() {
+            if self.broken {
+                break;
+            }
+            changed |= self.scan(byte);
+        }
+
+        if let Some(root_type) = self.root.and_then(|byte| self.root_type(byte)) {
+            if !self.options.allowed_roots.contains(&root_type) {
+                self.complete = None;
+                self.broken = true;
+                return FeedResult::Invalid(self.options.unexpected_root(root_type));
+            }
+        }
+        if self.broken {
+            return self.verdict();
+        }
+        match self.root {
+            None => FeedResult::NeedMore,
+            Some(b'[' | b'{' | b'"') if self.closed => match self.complete {
+                Some(root_type) if !changed => FeedResult::Complete(root_type),
+                _ => self.verdict(),
+            },
+            Some(b'[' | b'{' | b'"') => FeedResult::NeedMore,
+            Some(_) => match self.verdict() {
+                FeedResult::Invalid(error)
+                    if error.category() == ErrorCategory::Syntax && self.is_scalar_prefix() =>
+                {
+                    FeedResult::NeedMore
+                }
+                verdict => verdict,
+            },
+        }
+    }
+
+    /// Updates the scanner state for one byte, returning whether it was part
+    /// of the content rather than whitespace.
+    fn scan(&mut self, byte: u8) -> bool {
+        if self.in_string {
+            match byte {
+                _ if self.escaped => self.escaped = false,
+                b'\\' => self.escaped = true,
+                b'"' => {
+                    self.in_string = false;
+                    self.closed = self.open.is_empty();
+                }
+                _ => {}
+            }
+            return true;
+        }
+        if byte.is_ascii_whitespace() {
+            return false;
+        }
+        if self.closed {
+            // Content after the root value
+            self.broken = true;
+            return true;
+        }
+        let root = *self.root.get_or_insert(byte);
+        if !matches!(root, b'[' | b'{' | b'"') {
+            // Scalar roots are checked by the parser as a whole
+            return true;
+        }
+        match byte {
+            b'"' => self.in_string = true,
+            b'[' | b'{' => self.open.push(byte),
+            b']' | b'}' => match self.open.pop() {
+                Some(open) if open == byte - 2 => self.closed = self.open.is_empty(),
+                _ => self.broken = true,
+            },
+            _ => {}
+        }
+        true
+    }
+
+    /// Returns the root type a value starting with `first` would have, or
+    /// `None` if no value starts with it.
+    fn root_type(&self, first: u8) -> Option<JsonRootType> {
+        match first {
+            b'{' => Some(JsonRootType::Object),
+            b'[' => Some(JsonRootType::Array),
+            b'"' => Some(JsonRootType::String),
+            b't' | b'f' => Some(JsonRootType::Boolean),
+            b'n' => Some(JsonRootType::Null),
+            b'-' | b'0'..=b'9' => Some(JsonRootType::Number),
+            b'N' | b'I' if self.options.allow_js_numbers => Some(JsonRootType::Number),
+            _ => None,
+        }
+    }
+
+    /// Runs the full parser over the input so far.
+    fn verdict(&mut self) -> FeedResult {
+        match JsonDocument::parse_with_options(&self.buffer, &self.options) {
+            Ok(document) => {
+                let root_type = document.root_type;
+                self.complete = Some(root_type);
+                FeedResult::Complete(root_type)
+            }
+            Err(error) => {
+                self.complete = None;
+                self.broken = true;
+                FeedResult::Invalid(error)
+            }
+        }
+    }
+
+    /// Whether a scalar root could still become valid: a truncated literal
+    /// such as `tr`, or a number missing its last digits such as `1.` or `-`.
+    fn is_scalar_prefix(&mut self) -> bool {
+        let text = self.buffer.trim_start();
+        let js_literals: &[&str] = if self.options.allow_js_numbers {
+            &["NaN", "Infinity", "-Infinity"]
+        } else {
+            &[]
+        };
+        let plausible = ["true", "false", "null"]
+            .iter()
+            .chain(js_literals)
+            .any(|literal| literal.starts_with(text))
+            || JsonDocument::validate_fragment(&format!("{}0", text), Rule::number);
+        self.broken = !plausible;
+        plausible
+    }
+}
@@ -0,0 +1,2435 @@
+//! # JSON Values
+//!
+//! This module provides an owned tree representation of a parsed JSON document.
+
+use alloc::borrow::Cow;
+use alloc::collections::{btree_map, BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::mem;
+use core::ops::ControlFlow;
+use core::slice;
+use core::str::FromStr;
+
+use pest::iterators::Pair;
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::ser;
+use crate::{
+    DuplicateKeyPolicy, JsonDocument, JsonNumber, JsonParseError, ObjectBacking, ParseOptions,
+    Rule, SerializeOptions,
+};
+
+/// Represents a single JSON value.
+#[derive(Debug, Clone)]
+pub enum JsonValue {
+    /// The `null` literal
+    Null,
+    /// A `true` or `false` literal
+    Bool(bool),
+    /// A number, with integers kept exact where they fit in 64 bits
+    Number(JsonNumber),
+    /// A string with all escape sequences decoded
+    String(String),
+    /// An array of values
+    Array(Vec<JsonValue>),
+    /// An object, with members kept in source order
+    Object(Vec<(String, JsonValue)>),
+    /// An object stored sorted by key, built with `ObjectBacking::Map`
+    Map(BTreeMap<String, JsonValue>),
+}
+
+/// The kind of a `JsonValue`, without its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonValueType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonValueType {
+    /// Returns the lowercase JSON name of this type, such as `"number"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            JsonValueType::Null => "null",
+            JsonValueType::Bool => "boolean",
+            JsonValueType::Number => "number",
+            JsonValueType::String => "string",
+            JsonValueType::Array => "array",
+            JsonValueType::Object => "object",
+        }
+    }
+}
+
+/// How `JsonValue::merge` combines two arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The overriding array replaces the base array
+    Replace,
+    /// The overriding array's elements are appended to the base array
+    Concat,
+}
+
+/// What `JsonValue::walk_mut` does after visiting a node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalkAction {
+    /// Descends into the node's children
+    Continue,
+    /// Leaves the node's children unvisited
+    SkipChildren,
+    /// Replaces the node with the given value, whose children are not visited
+    Replace(JsonValue),
+}
+
+/// Iterator over the members of an `Object` or `Map`, in storage order.
+pub(crate) enum Members<'a> {
+    Ordered(slice::Iter<'a, (String, JsonValue)>),
+    Sorted(btree_map::Iter<'a, String, JsonValue>),
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = (&'a str, &'a JsonValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Members::Ordered(iter) => iter.next().map(|(k, v)| (k.as_str(), v)),
+            Members::Sorted(iter) => iter.next().map(|(k, v)| (k.as_str(), v)),
+        }
+    }
+}
+
+/// Compares values structurally, treating numbers by numeric value.
+///
+/// `1`, `1.0`, and `1e0` compare equal, as do `0` and `-0`, while distinct
+/// 64-bit integers never compare equal just because they round to the same
+/// `f64`. Objects are equal when they hold the same keys with equal values,
+/// in any order, so `{"a":1,"b":2}` equals `{"b":2,"a":1}`; only the relative
+/// order of a repeated key's values matters. A `Map` equals an `Object` in the
+/// same way. Use `eq_ordered` to also require the same member order.
+impl PartialEq for JsonValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JsonValue::Null, JsonValue::Null) => true,
+            (JsonValue::Bool(a), JsonValue::Bool(b)) => a == b,
+            (JsonValue::Number(a), JsonValue::Number(b)) => a == b,
+            (JsonValue::String(a), JsonValue::String(b)) => a == b,
+            (JsonValue::Array(a), JsonValue::Array(b)) => a == b,
+            (JsonValue::Object(a), JsonValue::Object(b)) => {
+                a.len() == b.len() && sorted_by_key(a) == sorted_by_key(b)
+            }
+            (JsonValue::Map(a), JsonValue::Map(b)) => a == b,
+            (JsonValue::Map(map), JsonValue::Object(members))
+            | (JsonValue::Object(members), JsonValue::Map(map)) => {
+                map.len() == members.len() && members.iter().all(|(k, v)| map.get(k) == Some(v))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns references to `members` ordered by key, keeping repeated keys in
+/// their original order.
+fn sorted_by_key(members: &[(String, JsonValue)]) -> Vec<&(String, JsonValue)> {
+    let mut sorted: Vec<_> = members.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    sorted
+}
+
+/// Formats the value as compact JSON text.
+///
+/// Object members keep their stored order and strings are re-escaped, so
+/// `{"a": [1, 2.5]}` is written as `{"a":[1,2.5]}`.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ser::write_value(f, self, &SerializeOptions::default(), 0)
+    }
+}
+
+/// Parses JSON text into a value like `JsonValue::parse`, allowing the
+/// `str::parse` form.
+///
+/// # Examples
+///
+/// ```
+/// use json_parser::JsonValue;
+///
+/// let value: JsonValue = "42".parse().unwrap();
+/// assert_eq!(value, JsonValue::from(42));
+/// ```
+impl FromStr for JsonValue {
+    type Err = JsonParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        JsonValue::parse(input)
+    }
+}
+
+/// Parses JSON text into a value like `JsonValue::parse`.
+///
+/// The text is parsed, not wrapped: `"42"` becomes a number. To build a
+/// `JsonValue::String` from a Rust string, use the `JsonValue::String`
+/// variant or pass the string to a builder.
+///
+/// # Examples
+///
+/// ```
+/// use json_parser::JsonValue;
+///
+/// let value: JsonValue = r#"{"id": 7}"#.try_into().unwrap();
+/// assert_eq!(value.get("id"), Some(&JsonValue::from(7)));
+///
+/// let value: JsonValue = "42".try_into().unwrap();
+/// assert_eq!(value, JsonValue::from(42));
+/// ```
+impl TryFrom<&str> for JsonValue {
+    type Error = JsonParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        JsonValue::parse(input)
+    }
+}
+
+/// Parses JSON text into a value like `JsonValue::parse`.
+impl TryFrom<String> for JsonValue {
+    type Error = JsonParseError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        JsonValue::parse(&input)
+    }
+}
+
+impl JsonValue {
+    /// Parses JSON text straight into a value, without a `JsonDocument`.
+    ///
+    /// Unlike `JsonDocument::parse`, any root type is accepted, so `"42"`
+    /// yields a number and `"\"hi\""` a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_parser::JsonValue;
+    ///
+    /// let user = JsonValue::parse(r#"{"name": "Ada", "admin": true}"#).unwrap();
+    /// assert_eq!(user.get("name"), Some(&JsonValue::String("Ada".to_string())));
+    ///
+    /// assert_eq!(JsonValue::parse(" null ").unwrap(), JsonValue::Null);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsonParseError` if the input is not valid JSON.
+    pub fn parse(input: &str) -> Result<JsonValue, JsonParseError> {
+        // Every root type is allowed, so the tree is built straight from the
+        // single grammar parse
+        JsonDocument::with_trimmed(input, |trimmed| {
+            let root = JsonDocument::root_pair_with(trimmed, Rule::document)?;
+            JsonValue::from_pair(root, &ParseOptions::any_root())
+        })
+    }
+
+    /// Builds a `JsonValue` from a pest pair matched by one of the value rules.
+    ///
+    /// Values are counted as they are built, failing with `TooManyValues` as
+    /// soon as `options.max_values` is exceeded.
+    pub(crate) fn from_pair(
+        pair: Pair<Rule>,
+        options: &ParseOptions,
+    ) -> Result<Self, JsonParseError> {
+        Self::build(pair, options, &mut 0)
+    }
+
+    /// Builds a value like `from_pair`, adding the values built to `values`.
+    fn build(
+        pair: Pair<Rule>,
+        options: &ParseOptions,
+        values: &mut usize,
+    ) -> Result<Self, JsonParseError> {
+        *values += 1;
+        if let Some(limit) = options.max_values {
+            if *values > limit {
+                return Err(JsonParseError::TooManyValues { limit });
+            }
+        }
+
+        match pair.as_rule() {
+            Rule::null => Ok(JsonValue::Null),
+            Rule::boolean => Ok(JsonValue::Bool(pair.as_str() == "true")),
+            Rule::number => {
+                let number = if options.preserve_number_text {
+                    JsonNumber::Raw(pair.as_str().to_string())
+                } else {
+                    JsonNumber::from_token(pair.as_str())
+                };
+                if options.reject_overflowing_numbers && number.is_out_of_range() {
+                    return Err(JsonParseError::NumberOutOfRange {
+                        token: pair.as_str().to_string(),
+                        line: pair.line_col().0,
+                    });
+                }
+                Ok(JsonValue::Number(number))
+            }
+            Rule::js_number => Ok(JsonValue::Number(JsonNumber::from_js_token(pair.as_str()))),
+            Rule::string => Ok(JsonValue::String(normalize(decode_string(pair)?, options))),
+            Rule::array => pair
+                .into_inner()
+                .map(|item| JsonValue::build(item, options, values))
+                .collect::<Result<Vec<_>, _>>()
+                .map(JsonValue::Array),
+            Rule::object => {
+                let members = pair
+                    .into_inner()
+                    .map(|member| JsonValue::member_from_pair(member, options, values))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let members = resolve_duplicates(members, options.duplicate_keys)?;
+                Ok(match options.object_backing {
+                    ObjectBacking::Ordered => JsonValue::Object(members),
+                    ObjectBacking::Map => JsonValue::Map(members.into_iter().collect()),
+                })
+            }
+            rule => Err(JsonParseError::UnexpectedRootType(format!("{:?}", rule))),
+        }
+    }
+
+    /// Builds a decoded key and value from a `pair` rule inside an object,
+    /// adding the values built to `values`.
+    pub(crate) fn member_from_pair(
+        pair: Pair<Rule>,
+        options: &ParseOptions,
+        values: &mut usize,
+    ) -> Result<(String, Self), JsonParseError> {
+        let mut inner = pair.into_inner();
+        let key = inner.next().ok_or(JsonParseError::EmptyJson)?;
+        let value = inner.next().ok_or(JsonParseError::EmptyJson)?;
+        Ok((
+            normalize(decode_string(key)?, options),
+            JsonValue::build(value, options, values)?,
+        ))
+    }
+
+    /// Returns an iterator over the members of an `Object` or `Map`.
+    pub(crate) fn members(&self) -> Option<Members<'_>> {
+        match self {
+            JsonValue::Object(members) => Some(Members::Ordered(members.iter())),
+            JsonValue::Map(map) => Some(Members::Sorted(map.iter())),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the members of an object in storage order.
+    ///
+    /// Yields nothing if this is not an object.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &JsonValue)> {
+        self.members().into_iter().flatten()
+    }
+
+    /// Iterates over the elements of an array in order.
+    ///
+    /// Yields nothing if this is not an array.
+    pub fn elements(&self) -> impl Iterator<Item = &JsonValue> {
+        match self {
+            JsonValue::Array(items) => items.iter(),
+            _ => [].iter(),
+        }
+    }
+
+    /// Compares like `==`, but also requires object members to appear in the
+    /// same order at every depth.
+    ///
+    /// Members are compared in storage order, so a `Map`, which keeps its
+    /// keys sorted, only equals an `Object` whose keys are sorted too.
+    pub fn eq_ordered(&self, other: &JsonValue) -> bool {
+        match (self, other) {
+            (JsonValue::Array(a), JsonValue::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.eq_ordered(y))
+            }
+            (
+                JsonValue::Object(_) | JsonValue::Map(_),
+                JsonValue::Object(_) | JsonValue::Map(_),
+            ) => {
+                self.len() == other.len()
+                    && self
+                        .entries()
+                        .zip(other.entries())
+                        .all(|((ka, va), (kb, vb))| ka == kb && va.eq_ordered(vb))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Returns the size of this value:
+    ///
+    /// - `Object` and `Map`: the number of members, counting a repeated key
+    ///   once per occurrence in an `Object`
+    /// - `Array`: the number of elements
+    /// - `String`: the number of characters, not bytes
+    /// - `Null`, `Bool`, and `Number`: 0
+    pub fn len(&self) -> usize {
+        match self {
+            JsonValue::Object(members) => members.len(),
+            JsonValue::Map(map) => map.len(),
+            JsonValue::Array(items) => items.len(),
+            JsonValue::String(s) => s.chars().count(),
+            JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => 0,
+        }
+    }
+
+    /// Returns whether this value is empty:
+    ///
+    /// - `Object` and `Map`: true with no members
+    /// - `Array`: true with no elements
+    /// - `String`: true for `""`
+    /// - `Null`: always true
+    /// - `Bool` and `Number`: always false, even for `false` and `0`
+    pub fn is_empty(&self) -> bool {
+        match self {
+            JsonValue::Null => true,
+            JsonValue::Bool(_) | JsonValue::Number(_) => false,
+            JsonValue::String(s) => s.is_empty(),
+            _ => self.len() == 0,
+        }
+    }
+
+    /// Returns the kind of this value; `Map` reports as `Object`.
+    pub fn value_type(&self) -> JsonValueType {
+        match self {
+            JsonValue::Null => JsonValueType::Null,
+            JsonValue::Bool(_) => JsonValueType::Bool,
+            JsonValue::Number(_) => JsonValueType::Number,
+            JsonValue::String(_) => JsonValueType::String,
+            JsonValue::Array(_) => JsonValueType::Array,
+            JsonValue::Object(_) | JsonValue::Map(_) => JsonValueType::Object,
+        }
+    }
+
+    /// Returns the lowercase JSON name of this value's type, such as `"number"`.
+    pub fn type_name(&self) -> &'static str {
+        self.value_type().name()
+    }
+
+    /// Returns the value of a `Bool`, or `None` for any other variant.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns a `Number` as an `i64` if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JsonValue::Number(n) => n.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// Returns a `Number` as an `f64`, rounding large integers.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(n.as_f64()),
+            _ => None,
+        }
+    }
+
+    /// Like `as_bool`, but also accepts the strings `"true"` and `"false"`,
+    /// exactly as written, with no surrounding whitespace or other casing.
+    pub fn as_bool_coerced(&self) -> Option<bool> {
+        match self {
+            JsonValue::String(s) => match s.as_str() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+            _ => self.as_bool(),
+        }
+    }
+
+    /// Like `as_i64`, but also accepts a string holding a JSON number literal
+    /// with an integral value in range, such as `"42"`, `"-7"`, or `"1e3"`.
+    ///
+    /// The whole string must be the literal, so `" 42"`, `"+42"`, and `"0x2A"`
+    /// are rejected.
+    pub fn as_i64_coerced(&self) -> Option<i64> {
+        match self {
+            JsonValue::String(s) => number_in_string(s)?.as_i64(),
+            _ => self.as_i64(),
+        }
+    }
+
+    /// Like `as_f64`, but also accepts a string holding a JSON number literal
+    /// such as `"2.5"` or `"-1e-3"`.
+    ///
+    /// The same string forms as `as_i64_coerced` are rejected, as are literals
+    /// too large for `f64` and spellings such as `"NaN"` or `"inf"`.
+    pub fn as_f64_coerced(&self) -> Option<f64> {
+        match self {
+            JsonValue::String(s) => Some(number_in_string(s)?.as_f64()).filter(|n| n.is_finite()),
+            _ => self.as_f64(),
+        }
+    }
+
+    /// Looks up a member of an object by key.
+    ///
+    /// Returns `None` if this is not an object or the key is absent. For an
+    /// `Object` holding duplicate keys the last member wins, matching the
+    /// behavior of the `Map` backing. A `Map` is searched in O(log n); an
+    /// `Object` is scanned linearly.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => {
+                members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            JsonValue::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// Looks up a value by JSON Pointer (RFC 6901), such as `/a/0/b`.
+    ///
+    /// The empty pointer refers to this value. Returns `None` if the pointer
+    /// is malformed or any segment does not exist. Array indices must be
+    /// written as RFC 6901 requires, so `01`, `+1`, and indices too large for
+    /// `usize` match nothing, as does `-`, which names the slot past the end.
+    pub fn pointer(&self, ptr: &str) -> Option<&JsonValue> {
+        if ptr.is_empty() {
+            return Some(self);
+        }
+        let tokens = ptr.strip_prefix('/')?;
+        tokens.split('/').try_fold(self, |current, token| {
+            let token = unescape_pointer_token(token);
+            match current {
+                JsonValue::Array(items) => items.get(array_index(&token)?),
+                _ => current.get(&token),
+            }
+        })
+    }
+
+    /// Returns true if the JSON Pointer `ptr` resolves, even to a `null` value.
+    pub fn has_pointer(&self, ptr: &str) -> bool {
+        self.pointer(ptr).is_some()
+    }
+
+    /// Returns true if this is an object with a member named `key`.
+    ///
+    /// Only direct members are checked; use `has_pointer` for nested paths.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Checks that this is an object with every key in `required` and, unless
+    /// `allow_extra` is set, no other keys.
+    ///
+    /// This is a lightweight guard for requests that must carry exactly a
+    /// known set of fields; only top-level keys are checked, not their values.
+    ///
+    /// # Errors
+    ///
+    /// Returns one message per problem: `missing required key '...'` for each
+    /// absent key in the order of `required`, then `unexpected key '...'` for
+    /// each other key in document order. A value that is not an object yields
+    /// a single `expected an object, found ...` message.
+    pub fn check_root_shape(
+        &self,
+        required: &[&str],
+        allow_extra: bool,
+    ) -> Result<(), Vec<String>> {
+        if self.value_type() != JsonValueType::Object {
+            return Err(vec![format!(
+                "expected an object, found {}",
+                self.type_name()
+            )]);
+        }
+
+        let mut problems: Vec<String> = required
+            .iter()
+            .filter(|key| !self.contains_key(key))
+            .map(|key| format!("missing required key '{}'", key))
+            .collect();
+        if !allow_extra {
+            let mut seen = BTreeSet::new();
+            problems.extend(
+                self.entries()
+                    .map(|(key, _)| key)
+                    .filter(|key| !required.contains(key) && seen.insert(*key))
+                    .map(|key| format!("unexpected key '{}'", key)),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Looks up a value by JSON Pointer for modification, resolving like `pointer`.
+    fn pointer_mut(&mut self, ptr: &str) -> Option<&mut JsonValue> {
+        if ptr.is_empty() {
+            return Some(self);
+        }
+        let tokens = ptr.strip_prefix('/')?;
+        tokens.split('/').try_fold(self, |current, token| {
+            let token = unescape_pointer_token(token);
+            match current {
+                JsonValue::Array(items) => items.get_mut(array_index(&token)?),
+                _ => current.get_mut(&token),
+            }
+        })
+    }
+
+    /// Removes the value at the JSON Pointer `ptr` and returns it without cloning.
+    ///
+    /// An object member is removed along with its key; if the key is
+    /// duplicated, only the last member, the one `pointer` resolves to, is
+    /// removed. An array element is removed and later elements shift down one
+    /// index, as with `Vec::remove`. The empty pointer takes this whole value,
+    /// leaving `Null` in its place. Returns `None`, leaving the tree unchanged,
+    /// if the pointer does not resolve.
+    pub fn take_pointer(&mut self, ptr: &str) -> Option<JsonValue> {
+        if ptr.is_empty() {
+            return Some(mem::replace(self, JsonValue::Null));
+        }
+        let (parent, token) = ptr.rsplit_once('/')?;
+        let token = unescape_pointer_token(token);
+        match self.pointer_mut(parent)? {
+            JsonValue::Array(items) => {
+                let index = array_index(&token).filter(|&i| i < items.len())?;
+                Some(items.remove(index))
+            }
+            JsonValue::Object(members) => {
+                let index = members.iter().rposition(|(k, _)| *k == token)?;
+                Some(members.remove(index).1)
+            }
+            JsonValue::Map(map) => map.remove(token.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns an owned deep copy of the value at the JSON Pointer `ptr`,
+    /// leaving this value intact, or `None` if the pointer does not resolve.
+    pub fn clone_pointer(&self, ptr: &str) -> Option<JsonValue> {
+        self.pointer(ptr).cloned()
+    }
+
+    /// Parses the string at `ptr` as a nested JSON document.
+    ///
+    /// This unwraps double-encoded JSON, such as a message whose `payload`
+    /// field holds a serialized object.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoStringAtPointer` if the pointer does not resolve to a string,
+    /// or any error `JsonDocument::parse` returns for the embedded text.
+    pub fn parse_embedded(&self, ptr: &str) -> Result<JsonValue, JsonParseError> {
+        match self.pointer(ptr) {
+            Some(JsonValue::String(embedded)) => JsonDocument::parse(embedded)?.to_value(),
+            _ => Err(JsonParseError::NoStringAtPointer(ptr.to_string())),
+        }
+    }
+
+    /// Deep-merges `other` into this value, as when layering an override
+    /// config over a base config.
+    ///
+    /// Objects merge key by key, recursing into members present in both; new
+    /// keys are appended. Arrays are combined according to `strategy`. In every
+    /// other case, including a type conflict such as a scalar against an
+    /// object, the value from `other` wins and replaces this one.
+    pub fn merge(&mut self, other: &JsonValue, strategy: MergeStrategy) {
+        match (self, other) {
+            (JsonValue::Array(base), JsonValue::Array(items))
+                if strategy == MergeStrategy::Concat =>
+            {
+                base.extend(items.iter().cloned());
+            }
+            (
+                base @ (JsonValue::Object(_) | JsonValue::Map(_)),
+                JsonValue::Object(_) | JsonValue::Map(_),
+            ) => {
+                for (key, value) in other.members().into_iter().flatten() {
+                    match base.get_mut(key) {
+                        Some(existing) => existing.merge(value, strategy),
+                        None => base.insert(key.to_string(), value.clone()),
+                    }
+                }
+            }
+            (base, _) => *base = other.clone(),
+        }
+    }
+
+    /// Applies a JSON Merge Patch (RFC 7396) to this value, as REST APIs do
+    /// for partial updates.
+    ///
+    /// If `patch` is an object, each of its members is applied in turn: a
+    /// `null` removes every member with that key, and any other value is
+    /// merge-patched into the member, which is added if missing. A target that
+    /// is not an object is first replaced by an empty one. If `patch` is not an
+    /// object, it replaces this value wholesale, so arrays are never merged.
+    pub fn apply_merge_patch(&mut self, patch: &JsonValue) {
+        if !matches!(patch, JsonValue::Object(_) | JsonValue::Map(_)) {
+            *self = patch.clone();
+            return;
+        }
+        if !matches!(self, JsonValue::Object(_) | JsonValue::Map(_)) {
+            *self = JsonValue::Object(Vec::new());
+        }
+        for (key, value) in patch.entries() {
+            match (&mut *self, value) {
+                (JsonValue::Object(members), JsonValue::Null) => members.retain(|(k, _)| k != key),
+                (JsonValue::Map(map), JsonValue::Null) => {
+                    map.remove(key);
+                }
+                (target, _) => {
+                    if target.get(key).is_none() {
+                        target.insert(key.to_string(), JsonValue::Null);
+                    }
+                    if let Some(member) = target.get_mut(key) {
+                        member.apply_merge_patch(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Recursively sorts the members of every object by key, in byte order.
+    ///
+    /// Array element order is left untouched, and members sharing a key keep
+    /// their relative order, so sorting is idempotent. Together with `Display`
+    /// this gives a canonical text form suitable for hashing.
+    pub fn sort_keys(&mut self) {
+        match self {
+            JsonValue::Object(members) => {
+                members.sort_by(|(a, _), (b, _)| a.cmp(b));
+                members.iter_mut().for_each(|(_, value)| value.sort_keys());
+            }
+            JsonValue::Map(map) => map.values_mut().for_each(JsonValue::sort_keys),
+            JsonValue::Array(items) => items.iter_mut().for_each(JsonValue::sort_keys),
+            _ => {}
+        }
+    }
+
+    /// Rewrites this value into canonical form: object members sorted by key
+    /// and every number in its narrowest exact representation, so `1.0`
+    /// becomes `1` and `-0` becomes `0`.
+    ///
+    /// Compact serialization of a canonical value has no insignificant
+    /// whitespace, so documents that differ only in formatting, key order, or
+    /// number spelling serialize identically afterwards.
+    pub fn canonicalize(&mut self) {
+        self.sort_keys();
+        self.canonicalize_numbers();
+    }
+
+    /// Returns the UTF-8 bytes of the canonical compact serialization, as
+    /// produced by `canonicalize`, for hashing or signing.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut canonical = self.clone();
+        canonical.canonicalize();
+        canonical.to_string().into_bytes()
+    }
+
+    fn canonicalize_numbers(&mut self) {
+        match self {
+            JsonValue::Number(n) => {
+                *n = match (n.as_i64(), n.as_u64()) {
+                    (Some(i), _) => JsonNumber::I64(i),
+                    (None, Some(u)) => JsonNumber::U64(u),
+                    _ => n
+                        .parse_raw()
+                        .unwrap_or_else(|| mem::replace(n, JsonNumber::I64(0))),
+                }
+            }
+            JsonValue::Array(items) => items.iter_mut().for_each(JsonValue::canonicalize_numbers),
+            JsonValue::Object(members) => members
+                .iter_mut()
+                .for_each(|(_, value)| value.canonicalize_numbers()),
+            JsonValue::Map(map) => map.values_mut().for_each(JsonValue::canonicalize_numbers),
+            _ => {}
+        }
+    }
+
+    /// Replaces every string value and every object key in this tree with the
+    /// result of `f`, recursing through arrays and nested objects.
+    ///
+    /// Object member order is kept. In a `Map`, keys that `f` maps to the same
+    /// string collapse into one member, keeping the value of the key that
+    /// sorted last.
+    pub fn map_strings<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        self.map_strings_inner(&mut f);
+    }
+
+    fn map_strings_inner<F: FnMut(&str) -> String>(&mut self, f: &mut F) {
+        match self {
+            JsonValue::String(s) => *s = f(s),
+            JsonValue::Array(items) => items.iter_mut().for_each(|item| item.map_strings_inner(f)),
+            JsonValue::Object(members) => {
+                for (key, value) in members.iter_mut() {
+                    *key = f(key);
+                    value.map_strings_inner(f);
+                }
+            }
+            JsonValue::Map(map) => {
+                *map = mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        value.map_strings_inner(f);
+                        (f(&key), value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes every object member, at any depth, for which `f(key, value)`
+    /// returns false, then recurses into the members that remain.
+    ///
+    /// Objects nested inside arrays are visited too; array elements themselves
+    /// are never removed, see `retain_elements`.
+    pub fn retain<F: FnMut(&str, &JsonValue) -> bool>(&mut self, mut f: F) {
+        self.retain_inner(&mut f);
+    }
+
+    fn retain_inner<F: FnMut(&str, &JsonValue) -> bool>(&mut self, f: &mut F) {
+        match self {
+            JsonValue::Array(items) => items.iter_mut().for_each(|item| item.retain_inner(f)),
+            JsonValue::Object(members) => {
+                members.retain(|(key, value)| f(key, value));
+                members
+                    .iter_mut()
+                    .for_each(|(_, value)| value.retain_inner(f));
+            }
+            JsonValue::Map(map) => {
+                map.retain(|key, value| f(key, value));
+                map.values_mut().for_each(|value| value.retain_inner(f));
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes every array element, at any depth, for which `f` returns
+    /// false, then recurses into the elements and object members that remain.
+    pub fn retain_elements<F: FnMut(&JsonValue) -> bool>(&mut self, mut f: F) {
+        self.retain_elements_inner(&mut f);
+    }
+
+    fn retain_elements_inner<F: FnMut(&JsonValue) -> bool>(&mut self, f: &mut F) {
+        match self {
+            JsonValue::Array(items) => {
+                items.retain(|item| f(item));
+                items
+                    .iter_mut()
+                    .for_each(|item| item.retain_elements_inner(f));
+            }
+            JsonValue::Object(members) => members
+                .iter_mut()
+                .for_each(|(_, value)| value.retain_elements_inner(f)),
+            JsonValue::Map(map) => map
+                .values_mut()
+                .for_each(|value| value.retain_elements_inner(f)),
+            _ => {}
+        }
+    }
+
+    /// Looks up a member of an object by key for modification, with the last
+    /// duplicate winning as in `get`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        match self {
+            JsonValue::Object(members) => members
+                .iter_mut()
+                .rev()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v),
+            JsonValue::Map(map) => map.get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Appends a member to an object, or inserts it into a map; a no-op for
+    /// other values.
+    pub(crate) fn insert(&mut self, key: String, value: JsonValue) {
+        match self {
+            JsonValue::Object(members) => members.push((key, value)),
+            JsonValue::Map(map) => {
+                map.insert(key, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the canonical text of a number, or `None` for other values.
+    ///
+    /// Numerically equal numbers share one canonical form, so `1`, `1.0`, and
+    /// `1e0` all yield `"1"`. The text is the shortest decimal that parses back
+    /// to the same `f64`, and `-0` is normalized to `0`. Magnitudes from 1e-6
+    /// up to 1e21 are written without an exponent; anything outside that range
+    /// uses a normalized exponent, so `1e300` stays `"1e300"` rather than 301
+    /// digits. Integer literals that fit in 64 bits keep every digit, so
+    /// `9007199254740993` canonicalizes to itself. Numbers beyond the range
+    /// of `f64` are returned as written.
+    pub fn canonical_number(&self) -> Option<String> {
+        match self {
+            JsonValue::Number(n @ JsonNumber::Raw(text)) if n.is_out_of_range() => {
+                Some(text.clone())
+            }
+            JsonValue::Number(n) => Some(match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => i.to_string(),
+                (None, Some(u)) => u.to_string(),
+                _ => canonical_f64(n.as_f64()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Converts a flat object of string values into a sorted map.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the offending key if any member is not a string,
+    /// or describing the value if it is not an object.
+    pub fn as_string_map(&self) -> Result<BTreeMap<String, String>, String> {
+        let Some(members) = self.members() else {
+            return Err("expected an object at the root".to_string());
+        };
+
+        let mut map = BTreeMap::new();
+        for (key, value) in members {
+            match value {
+                JsonValue::String(s) => {
+                    map.insert(key.to_string(), s.clone());
+                }
+                _ => return Err(format!("value for key '{}' is not a string", key)),
+            }
+        }
+        Ok(map)
+    }
+
+    /// Converts an object into a sorted map, rejecting duplicate keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DuplicateKey` naming the first repeated key, or
+    /// `UnexpectedRootType` if this value is not an object.
+    pub fn into_map(self) -> Result<BTreeMap<String, JsonValue>, JsonParseError> {
+        match self {
+            JsonValue::Object(members) => {
+                let mut map = BTreeMap::new();
+                for (key, value) in members {
+                    match map.entry(key) {
+                        btree_map::Entry::Occupied(entry) => {
+                            return Err(JsonParseError::DuplicateKey {
+                                key: entry.key().clone(),
+                            });
+                        }
+                        btree_map::Entry::Vacant(entry) => {
+                            entry.insert(value);
+                        }
+                    }
+                }
+                Ok(map)
+            }
+            JsonValue::Map(map) => Ok(map),
+            other => Err(JsonParseError::UnexpectedRootType(
+                other.type_name().to_string(),
+            )),
+        }
+    }
+
+    /// Converts an object into a sorted map, keeping the last value of a
+    /// duplicate key as `serde_json` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedRootType` if this value is not an object.
+    pub fn into_map_last_wins(self) -> Result<BTreeMap<String, JsonValue>, JsonParseError> {
+        match self {
+            JsonValue::Object(members) => Ok(members.into_iter().collect()),
+            JsonValue::Map(map) => Ok(map),
+            other => Err(JsonParseError::UnexpectedRootType(
+                other.type_name().to_string(),
+            )),
+        }
+    }
+
+    /// Groups the elements of an array of objects by the string at `key`.
+    ///
+    /// Elements that are not objects, lack `key`, or hold a non-string value
+    /// there are collected under `"unknown"`. Use `group_by_with` to treat
+    /// them as errors instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if this value is not an array.
+    pub fn group_by(&self, key: &str) -> Result<BTreeMap<String, Vec<&JsonValue>>, String> {
+        self.group_by_with(key, false)
+    }
+
+    /// Groups the elements of an array of objects by the string at `key`.
+    ///
+    /// When `strict` is false, ungroupable elements go to an `"unknown"`
+    /// bucket as in `group_by`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if this value is not an array, or, when `strict` is
+    /// true, naming the index of the first element without a string at `key`.
+    pub fn group_by_with(
+        &self,
+        key: &str,
+        strict: bool,
+    ) -> Result<BTreeMap<String, Vec<&JsonValue>>, String> {
+        let JsonValue::Array(items) = self else {
+            return Err(format!("expected an array, found {}", self.type_name()));
+        };
+
+        let mut groups: BTreeMap<String, Vec<&JsonValue>> = BTreeMap::new();
+        for (index, item) in items.iter().enumerate() {
+            let group = match item.get(key) {
+                Some(JsonValue::String(group)) => group.as_str(),
+                _ if strict => {
+                    return Err(format!(
+                        "element {} has no string value for '{}'",
+                        index, key
+                    ));
+                }
+                _ => "unknown",
+            };
+            groups.entry(group.to_string()).or_default().push(item);
+        }
+        Ok(groups)
+    }
+
+    /// Re-keys an array of objects into an object keyed by the string at `key`.
+    ///
+    /// `[{"id": "a"}, {"id": "b"}]` becomes `{"a": {"id": "a"}, "b": {"id": "b"}}`,
+    /// in array order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if this value is not an array, if an element has no
+    /// string at `key`, or if two elements share a key. Use `index_by_with`
+    /// to let later duplicates replace earlier ones.
+    pub fn index_by(&self, key: &str) -> Result<JsonValue, String> {
+        self.index_by_with(key, false)
+    }
+
+    /// Re-keys an array of objects into an object keyed by the string at `key`.
+    ///
+    /// When `last_wins` is true, a duplicate key replaces the earlier element
+    /// while keeping its position; otherwise duplicates are an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message under the same conditions as `index_by`, except for
+    /// duplicates when `last_wins` is true.
+    pub fn index_by_with(&self, key: &str, last_wins: bool) -> Result<JsonValue, String> {
+        let JsonValue::Array(items) = self else {
+            return Err(format!("expected an array, found {}", self.type_name()));
+        };
+
+        let mut positions: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut index: Vec<(String, JsonValue)> = Vec::with_capacity(items.len());
+        for (position, item) in items.iter().enumerate() {
+            let Some(JsonValue::String(id)) = item.get(key) else {
+                return Err(format!(
+                    "element {} has no string value for '{}'",
+                    position, key
+                ));
+            };
+            match positions.get(id.as_str()) {
+                Some(&existing) if last_wins => index[existing].1 = item.clone(),
+                Some(_) => return Err(format!("duplicate value '{}' for '{}'", id, key)),
+                None => {
+                    positions.insert(id, index.len());
+                    index.push((id.clone(), item.clone()));
+                }
+            }
+        }
+        Ok(JsonValue::Object(index))
+    }
+
+    /// Returns a lower-bound estimate of the memory held by this tree, in bytes.
+    ///
+    /// This counts the value itself plus the capacity of every string and
+    /// `Vec` it owns. Allocator overhead and the internal nodes of a `Map`
+    /// are not counted, so the true footprint is somewhat larger.
+    pub fn estimated_size(&self) -> usize {
+        mem::size_of::<JsonValue>() + self.heap_size()
+    }
+
+    /// Returns the heap bytes owned by this value, excluding the value itself.
+    fn heap_size(&self) -> usize {
+        match self {
+            JsonValue::Null | JsonValue::Bool(_) => 0,
+            JsonValue::Number(JsonNumber::Raw(text)) => text.capacity(),
+            JsonValue::Number(_) => 0,
+            JsonValue::String(s) => s.capacity(),
+            JsonValue::Array(items) => {
+                items.capacity() * mem::size_of::<JsonValue>()
+                    + items.iter().map(JsonValue::heap_size).sum::<usize>()
+            }
+            JsonValue::Object(members) => {
+                members.capacity() * mem::size_of::<(String, JsonValue)>()
+                    + members
+                        .iter()
+                        .map(|(key, value)| key.capacity() + value.heap_size())
+                        .sum::<usize>()
+            }
+            JsonValue::Map(map) => map
+                .iter()
+                .map(|(key, value)| {
+                    mem::size_of::<(String, JsonValue)>() + key.capacity() + value.heap_size()
+                })
+                .sum(),
+        }
+    }
+
+    /// Returns the container nesting depth of this value.
+    ///
+    /// Scalars have depth 0, and each enclosing object or array adds one, so
+    /// `{"a": [1]}` has depth 2.
+    pub fn depth(&self) -> usize {
+        match self {
+            JsonValue::Array(items) => 1 + items.iter().map(JsonValue::depth).max().unwrap_or(0),
+            JsonValue::Object(_) | JsonValue::Map(_) => {
+                1 + self
+                    .members()
+                    .into_iter()
+                    .flatten()
+                    .map(|(_, v)| v.depth())
+                    .max()
+                    .unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Counts the members named `key` anywhere in this tree.
+    ///
+    /// This counts members, not objects: an object holding `key` twice
+    /// contributes 2.
+    pub fn key_frequency(&self, key: &str) -> usize {
+        match self {
+            JsonValue::Array(items) => items.iter().map(|v| v.key_frequency(key)).sum(),
+            JsonValue::Object(_) | JsonValue::Map(_) => self
+                .members()
+                .into_iter()
+                .flatten()
+                .map(|(k, v)| usize::from(k == key) + v.key_frequency(key))
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    /// Collects every distinct object key used anywhere in this tree.
+    ///
+    /// Array indices are not keys and are never included.
+    pub fn all_keys(&self) -> BTreeSet<String> {
+        let mut keys = BTreeSet::new();
+        let _ = self.walk(|node| {
+            if let Some(members) = node.members() {
+                keys.extend(members.map(|(key, _)| key.to_string()));
+            }
+            ControlFlow::Continue(())
+        });
+        keys
+    }
+
+    /// Visits this value and every descendant in document order, parents first.
+    ///
+    /// Traversal stops as soon as `f` returns `ControlFlow::Break`, which is
+    /// then returned; otherwise the result is `ControlFlow::Continue`.
+    pub fn walk<F: FnMut(&JsonValue) -> ControlFlow<()>>(&self, mut f: F) -> ControlFlow<()> {
+        self.walk_inner(&mut f)
+    }
+
+    fn walk_inner<F: FnMut(&JsonValue) -> ControlFlow<()>>(&self, f: &mut F) -> ControlFlow<()> {
+        f(self)?;
+        match self {
+            JsonValue::Array(items) => items.iter().try_for_each(|item| item.walk_inner(f)),
+            JsonValue::Object(_) | JsonValue::Map(_) => self
+                .members()
+                .into_iter()
+                .flatten()
+                .try_for_each(|(_, value)| value.walk_inner(f)),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+
+    /// Visits this value and every descendant in document order, parents
+    /// first, passing each node's JSON Pointer (RFC 6901) and mutable access.
+    ///
+    /// The returned `WalkAction` decides whether the node's children are
+    /// visited or the node is replaced.
+    pub fn walk_mut<F: FnMut(&str, &mut JsonValue) -> WalkAction>(&mut self, mut f: F) {
+        let mut path = String::new();
+        self.walk_mut_inner(&mut path, &mut f);
+    }
+
+    fn walk_mut_inner<F: FnMut(&str, &mut JsonValue) -> WalkAction>(
+        &mut self,
+        path: &mut String,
+        f: &mut F,
+    ) {
+        match f(path, self) {
+            WalkAction::Continue => {}
+            WalkAction::SkipChildren => return,
+            WalkAction::Replace(value) => {
+                *self = value;
+                return;
+            }
+        }
+        let len = path.len();
+        match self {
+            JsonValue::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    path.push_str(&format!("/{}", index));
+                    item.walk_mut_inner(path, f);
+                    path.truncate(len);
+                }
+            }
+            JsonValue::Object(members) => {
+                for (key, value) in members.iter_mut() {
+                    path.push('/');
+                    path.push_str(&escape_pointer_token(key));
+                    value.walk_mut_inner(path, f);
+                    path.truncate(len);
+                }
+            }
+            JsonValue::Map(map) => {
+                for (key, value) in map.iter_mut() {
+                    path.push('/');
+                    path.push_str(&escape_pointer_token(key));
+                    value.walk_mut_inner(path, f);
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders a compact one-line description of this value for logging.
+    ///
+    /// Containers report their size and depth, such as `object{3 keys, depth 2}`
+    /// or `array[5 items, depth 1]`; scalars report their type, with strings
+    /// also giving their length in characters.
+    pub fn summary(&self) -> String {
+        fn plural(count: usize, noun: &str) -> String {
+            if count == 1 {
+                format!("{} {}", count, noun)
+            } else {
+                format!("{} {}s", count, noun)
+            }
+        }
+
+        match self {
+            JsonValue::Null => "null".to_string(),
+            JsonValue::Bool(_) => "boolean".to_string(),
+            JsonValue::Number(_) => "number".to_string(),
+            JsonValue::String(s) => format!("string({})", plural(s.chars().count(), "char")),
+            JsonValue::Array(items) => {
+                format!(
+                    "array[{}, depth {}]",
+                    plural(items.len(), "item"),
+                    self.depth()
+                )
+            }
+            JsonValue::Object(_) | JsonValue::Map(_) => {
+                let keys = self.members().into_iter().flatten().count();
+                format!("object{{{}, depth {}}}", plural(keys, "key"), self.depth())
+            }
+        }
+    }
+
+    /// Returns the JSON Pointer (RFC 6901) of every value, at any depth, for
+    /// which `pred` returns true, in document order.
+    ///
+    /// Containers are tested before their contents, and this value itself
+    /// is tested with the empty pointer.
+    pub fn find_paths<F: Fn(&JsonValue) -> bool>(&self, pred: F) -> Vec<String> {
+        let mut out = Vec::new();
+        self.find_paths_into(String::new(), &pred, &mut out);
+        out
+    }
+
+    fn find_paths_into<F: Fn(&JsonValue) -> bool>(
+        &self,
+        path: String,
+        pred: &F,
+        out: &mut Vec<String>,
+    ) {
+        if pred(self) {
+            out.push(path.clone());
+        }
+        match self {
+            JsonValue::Array(items) => {
+                for (index, value) in items.iter().enumerate() {
+                    value.find_paths_into(format!("{}/{}", path, index), pred, out);
+                }
+            }
+            _ => {
+                for (key, value) in self.entries() {
+                    let child = format!("{}/{}", path, escape_pointer_token(key));
+                    value.find_paths_into(child, pred, out);
+                }
+            }
+        }
+    }
+
+    /// Lists every leaf scalar keyed by its JSON Pointer (RFC 6901).
+    ///
+    /// `{"a": {"b": [1, 2]}}` yields `[("/a/b/0", 1), ("/a/b/1", 2)]`. Empty
+    /// objects and arrays are kept as leaves mapping to an empty container.
+    pub fn flatten(&self) -> Vec<(String, JsonValue)> {
+        self.flatten_with(true)
+    }
+
+    /// Lists every leaf scalar keyed by its JSON Pointer (RFC 6901).
+    ///
+    /// When `include_empty` is false, empty objects and arrays are omitted
+    /// instead of appearing as leaves.
+    pub fn flatten_with(&self, include_empty: bool) -> Vec<(String, JsonValue)> {
+        let mut out = Vec::new();
+        self.flatten_into(String::new(), include_empty, &mut out);
+        out
+    }
+
+    fn flatten_into(&self, path: String, include_empty: bool, out: &mut Vec<(String, JsonValue)>) {
+        match self {
+            JsonValue::Object(_) | JsonValue::Map(_) if !self.is_empty_container() => {
+                for (key, value) in self.members().into_iter().flatten() {
+                    let child = format!("{}/{}", path, escape_pointer_token(key));
+                    value.flatten_into(child, include_empty, out);
+                }
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                for (index, value) in items.iter().enumerate() {
+                    value.flatten_into(format!("{}/{}", path, index), include_empty, out);
+                }
+            }
+            JsonValue::Object(_) | JsonValue::Map(_) | JsonValue::Array(_) if !include_empty => {}
+            _ => out.push((path, self.clone())),
+        }
+    }
+
+    /// Returns true for an object or map without members.
+    fn is_empty_container(&self) -> bool {
+        match self {
+            JsonValue::Object(members) => members.is_empty(),
+            JsonValue::Map(map) => map.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Expands an object with separator-joined keys into nested objects.
+    ///
+    /// `{"a.b.c": 1}` with `sep = '.'` becomes `{"a": {"b": {"c": 1}}}`. Member
+    /// order follows the first appearance of each key segment.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if `flat` is not an object, or if a key both holds a
+    /// value and is used as a prefix of another key.
+    pub fn unflatten(flat: &JsonValue, sep: char) -> Result<JsonValue, String> {
+        let Some(members) = flat.members() else {
+            return Err("expected an object to unflatten".to_string());
+        };
+
+        let mut root = JsonValue::Object(Vec::new());
+        for (key, value) in members {
+            let segments: Vec<&str> = key.split(sep).collect();
+            let (last, parents) = segments.split_last().unwrap_or((&"", &[]));
+
+            let mut current = &mut root;
+            for segment in parents {
+                let JsonValue::Object(children) = current else {
+                    return Err(format!("key '{}' conflicts with an existing value", key));
+                };
+                let index = match children.iter().position(|(k, _)| k == segment) {
+                    Some(index) => index,
+                    None => {
+                        children.push((segment.to_string(), JsonValue::Object(Vec::new())));
+                        children.len() - 1
+                    }
+                };
+                current = &mut children[index].1;
+            }
+
+            let JsonValue::Object(children) = current else {
+                return Err(format!("key '{}' conflicts with an existing value", key));
+            };
+            if children.iter().any(|(k, _)| k == last) {
+                return Err(format!("key '{}' conflicts with an existing value", key));
+            }
+            children.push((last.to_string(), value.clone()));
+        }
+        Ok(root)
+    }
+}
+
+/// Applies `policy` to the members of one object, in source order.
+fn resolve_duplicates(
+    members: Vec<(String, JsonValue)>,
+    policy: DuplicateKeyPolicy,
+) -> Result<Vec<(String, JsonValue)>, JsonParseError> {
+    if policy == DuplicateKeyPolicy::KeepAll {
+        return Ok(members);
+    }
+
+    let mut positions: BTreeMap<String, usize> = BTreeMap::new();
+    let mut resolved: Vec<(String, JsonValue)> = Vec::with_capacity(members.len());
+    for (key, value) in members {
+        match (positions.get(&key), policy) {
+            (None, _) => {
+                positions.insert(key.clone(), resolved.len());
+                resolved.push((key, value));
+            }
+            (Some(_), DuplicateKeyPolicy::Error) => {
+                return Err(JsonParseError::DuplicateKey { key })
+            }
+            (Some(&index), DuplicateKeyPolicy::KeepLast) => resolved[index].1 = value,
+            (Some(_), _) => {}
+        }
+    }
+    Ok(resolved)
+}
+
+/// Formats a float as the shortest round-tripping decimal, switching to
+/// exponent notation outside `1e-6 <= |f| < 1e21` to keep the text bounded.
+fn canonical_f64(f: f64) -> String {
+    let magnitude = f.abs();
+    if f == 0.0 {
+        "0".to_string()
+    } else if (1e-6..1e21).contains(&magnitude) {
+        f.to_string()
+    } else {
+        format!("{:e}", f)
+    }
+}
+
+/// Escapes a key for use as a JSON Pointer reference token (RFC 6901).
+pub(crate) fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Reverses `escape_pointer_token`, turning `~1` into `/` and `~0` into `~`.
+pub(crate) fn unescape_pointer_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Parses a JSON Pointer token as an array index: `0` or digits without a
+/// leading zero, small enough for `usize`.
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    let digits = token.bytes().all(|b| b.is_ascii_digit());
+    if !digits || token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Takes ownership of a decoded string, applying `ParseOptions::normalize_nfc`.
+#[cfg(feature = "unicode")]
+fn normalize(s: Cow<'_, str>, options: &ParseOptions) -> String {
+    if options.normalize_nfc {
+        s.nfc().collect()
+    } else {
+        s.into_owned()
+    }
+}
+
+#[cfg(not(feature = "unicode"))]
+fn normalize(s: Cow<'_, str>, _options: &ParseOptions) -> String {
+    s.into_owned()
+}
+
+/// Parses `s` as a number if the whole string is a JSON number literal.
+fn number_in_string(s: &str) -> Option<JsonNumber> {
+    JsonDocument::validate_fragment(s, Rule::number).then(|| JsonNumber::from_token(s))
+}
+
+/// Decodes the contents of a `string` pair, resolving escape sequences.
+///
+/// UTF-16 surrogate pairs such as `\uD83D\uDE00` are combined into a single
+/// character; a surrogate without its partner is rejected.
+///
+/// The input is borrowed when it contains no escapes; otherwise a decoded copy
+/// is allocated.
+pub(crate) fn decode_string<'i>(pair: Pair<'i, Rule>) -> Result<Cow<'i, str>, JsonParseError> {
+    let Some(inner) = pair.into_inner().next() else {
+        return Ok(Cow::Borrowed(""));
+    };
+    let raw = inner.as_str();
+    if !raw.contains('\\') {
+        return Ok(Cow::Borrowed(raw));
+    }
+    let span = inner.as_span();
+
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+        let escape_start = raw.len() - rest.len() + index;
+        let mut chars = rest[index + 1..].chars();
+        let escape = chars.next().unwrap_or_default();
+        rest = chars.as_str();
+
+        let decoded = match escape {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{0008}',
+            'f' => '\u{000C}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let unpaired = |code: u32| {
+                    let (line, column) = line_col(&span, escape_start);
+                    JsonParseError::UnpairedSurrogate { code, line, column }
+                };
+                let high = take_hex4(&mut rest);
+                match high {
+                    0xD800..=0xDBFF => {
+                        let low = rest
+                            .strip_prefix("\\u")
+                            .map(|after| {
+                                let mut after = after;
+                                (take_hex4(&mut after), after)
+                            })
+                            .filter(|(low, _)| (0xDC00..=0xDFFF).contains(low));
+                        let Some((low, after)) = low else {
+                            return Err(unpaired(high));
+                        };
+                        rest = after;
+                        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                        char::from_u32(code).ok_or_else(|| unpaired(high))?
+                    }
+                    0xDC00..=0xDFFF => return Err(unpaired(high)),
+                    code => char::from_u32(code).ok_or_else(|| unpaired(code))?,
+                }
+            }
+            // The grammar only admits the escapes above
+            _ => continue,
+        };
+        out.push(decoded);
+    }
+    out.push_str(rest);
+    Ok(Cow::Owned(out))
+}
+
+/// Consumes four hex digits from the front of `rest` and returns their value.
+fn take_hex4(rest: &mut &str) -> u32 {
+    let digits = rest.get(..4).unwrap_or(rest);
+    *rest = &rest[digits.len()..];
+    u32::from_str_radix(digits, 16).unwrap_or(0xFFFD)
+}
+
+/// Returns the line and column of a byte offset within a span.
+fn line_col(span: &pest::Span, offset: usize) -> (usize, usize) {
+    pest::Position::new(span.get_input(), span.start() + offset)
+        .map(|pos| pos.line_col())
+        .unwrap_or_else(|| span.start_pos().line_col())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(json: &str) -> JsonValue {
+        JsonDocument::parse(json).unwrap().to_value().unwrap()
+    }
+
+    #[test]
+    fn test_to_value() {
+        let v = value(r#"{"a": [1, true, null], "b": "x\ny"}"#);
+        assert_eq!(
+            v,
+            JsonValue::Object(vec![
+                (
+                    "a".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::Number(JsonNumber::I64(1)),
+                        JsonValue::Bool(true),
+                        JsonValue::Null
+                    ])
+                ),
+                ("b".to_string(), JsonValue::String("x\ny".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_as_string_map() {
+        let map = value(r#"{"host": "localhost", "port": "8080"}"#)
+            .as_string_map()
+            .unwrap();
+        assert_eq!(map.get("host").map(String::as_str), Some("localhost"));
+        assert_eq!(map.get("port").map(String::as_str), Some("8080"));
+    }
+
+    #[test]
+    fn test_as_string_map_non_string_value() {
+        let err = value(r#"{"host": "localhost", "port": 8080}"#)
+            .as_string_map()
+            .unwrap_err();
+        assert!(err.contains("port"));
+    }
+
+    #[test]
+    fn test_large_integers_round_trip() {
+        let v = value("[9007199254740993, 18446744073709551615, -9223372036854775808, 2.5]");
+        let JsonValue::Array(items) = &v else {
+            panic!("expected an array");
+        };
+        assert_eq!(
+            items[0],
+            JsonValue::Number(JsonNumber::I64(9007199254740993))
+        );
+        assert_eq!(items[1], JsonValue::Number(JsonNumber::U64(u64::MAX)));
+        assert_eq!(items[2], JsonValue::Number(JsonNumber::I64(i64::MIN)));
+        assert_eq!(
+            v.to_string(),
+            "[9007199254740993,18446744073709551615,-9223372036854775808,2.5]"
+        );
+        assert_eq!(
+            items[0].canonical_number().as_deref(),
+            Some("9007199254740993")
+        );
+    }
+
+    #[test]
+    fn test_display_escapes_strings() {
+        let v = value(r#"{"a\"b": ["x\ny", 1.0, null, true], "c": {}}"#);
+        assert_eq!(v.to_string(), r#"{"a\"b":["x\ny",1,null,true],"c":{}}"#);
+    }
+
+    #[test]
+    fn test_canonical_number() {
+        for json in ["[1]", "[1.0]", "[1e0]", "[10e-1]"] {
+            let JsonValue::Array(items) = value(json) else {
+                panic!("expected array");
+            };
+            assert_eq!(items[0].canonical_number().as_deref(), Some("1"));
+        }
+        assert_eq!(value("[-0.0]"), value("[0]"));
+        assert_eq!(value(r#"{"x": 1}"#), value(r#"{"x": 1.0}"#));
+        assert_ne!(value(r#"{"x": 1}"#), value(r#"{"x": 1.5}"#));
+        assert_eq!(JsonValue::Null.canonical_number(), None);
+
+        let canonical = |json: &str| {
+            let JsonValue::Array(items) = value(json) else {
+                panic!("expected array");
+            };
+            items[0].canonical_number().unwrap()
+        };
+        assert_eq!(canonical("[1e300]"), "1e300");
+        assert_eq!(canonical("[-1e-300]"), "-1e-300");
+        assert_eq!(canonical("[1.5e-7]"), "1.5e-7");
+        assert_eq!(canonical("[0.25]"), "0.25");
+        assert_eq!(canonical("[1e20]"), "100000000000000000000");
+        assert!(canonical("[1.7976931348623157e308]").len() < 32);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let v = value(r#"{"a": {"b": [1, 2]}, "c/d": "x", "e": {}, "f": []}"#);
+        assert_eq!(
+            v.flatten(),
+            vec![
+                ("/a/b/0".to_string(), JsonValue::Number(JsonNumber::I64(1))),
+                ("/a/b/1".to_string(), JsonValue::Number(JsonNumber::I64(2))),
+                ("/c~1d".to_string(), JsonValue::String("x".to_string())),
+                ("/e".to_string(), JsonValue::Object(Vec::new())),
+                ("/f".to_string(), JsonValue::Array(Vec::new())),
+            ]
+        );
+        assert_eq!(v.flatten_with(false).len(), 3);
+    }
+
+    #[test]
+    fn test_value_type() {
+        let v = value(r#"[null, true, 1.5, "s", [], {}]"#);
+        let JsonValue::Array(items) = &v else {
+            panic!("expected array");
+        };
+        let names: Vec<&str> = items.iter().map(JsonValue::type_name).collect();
+        assert_eq!(
+            names,
+            ["null", "boolean", "number", "string", "array", "object"]
+        );
+        assert_eq!(items[2].value_type(), JsonValueType::Number);
+        assert_eq!(
+            JsonValue::Map(BTreeMap::new()).value_type(),
+            JsonValueType::Object
+        );
+    }
+
+    #[test]
+    fn test_into_map() {
+        let map = value(r#"{"b": 1, "a": 2}"#).into_map().unwrap();
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+
+        assert!(matches!(
+            value(r#"{"a": 1, "b": 2, "a": 3}"#).into_map(),
+            Err(JsonParseError::DuplicateKey { key }) if key == "a"
+        ));
+        assert!(matches!(
+            value("[1]").into_map(),
+            Err(JsonParseError::UnexpectedRootType(_))
+        ));
+
+        let map = value(r#"{"a": 1, "b": 2, "a": 3}"#)
+            .into_map_last_wins()
+            .unwrap();
+        assert_eq!(map.get("a"), Some(&JsonValue::Number(JsonNumber::I64(3))));
+    }
+
+    #[test]
+    fn test_pointer() {
+        let v = value(r#"{"a": [{"b": 1}], "c/d": 2, "e~f": 3, "": 4}"#);
+        assert_eq!(v.pointer(""), Some(&v));
+        assert_eq!(
+            v.pointer("/a/0/b"),
+            Some(&JsonValue::Number(JsonNumber::I64(1)))
+        );
+        assert_eq!(
+            v.pointer("/c~1d"),
+            Some(&JsonValue::Number(JsonNumber::I64(2)))
+        );
+        assert_eq!(
+            v.pointer("/e~0f"),
+            Some(&JsonValue::Number(JsonNumber::I64(3)))
+        );
+        assert_eq!(v.pointer("/"), Some(&JsonValue::Number(JsonNumber::I64(4))));
+        assert_eq!(v.pointer("/a/1"), None);
+        assert_eq!(v.pointer("a"), None);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(
+            JsonValue::parse("[1, {\"a\": null}]").unwrap(),
+            value("[1, {\"a\": null}]")
+        );
+        assert_eq!(JsonValue::parse("42").unwrap(), JsonValue::from(42));
+        assert_eq!(JsonValue::parse("-2.5e0").unwrap(), JsonValue::from(-2.5));
+        assert_eq!(
+            JsonValue::parse("\"hi\"").unwrap(),
+            JsonValue::String("hi".to_string())
+        );
+        assert_eq!(
+            "false".parse::<JsonValue>().unwrap(),
+            JsonValue::from(false)
+        );
+        assert!(matches!(
+            JsonValue::parse(""),
+            Err(JsonParseError::EmptyJson)
+        ));
+        assert!(JsonValue::parse("42 43").is_err());
+        assert!("[1,]".parse::<JsonValue>().is_err());
+    }
+
+    #[test]
+    fn test_try_from_str_parses() {
+        let number: JsonValue = "42".try_into().unwrap();
+        assert_eq!(number, JsonValue::from(42));
+        let quoted: JsonValue = "\"42\"".try_into().unwrap();
+        assert_eq!(quoted, JsonValue::String("42".to_string()));
+        let object = JsonValue::try_from(String::from("{\"a\": [true]}")).unwrap();
+        assert_eq!(object, value("{\"a\": [true]}"));
+        assert!(JsonValue::try_from("not json").is_err());
+    }
+
+    #[test]
+    fn test_check_root_shape() {
+        let request = value(r#"{"id": 1, "debug": true, "name": "a", "debug": false}"#);
+
+        assert_eq!(request.check_root_shape(&["id", "name"], true), Ok(()));
+        assert_eq!(
+            request.check_root_shape(&["id", "name", "debug"], false),
+            Ok(())
+        );
+        assert_eq!(
+            request.check_root_shape(&["id", "email", "token"], false),
+            Err(vec![
+                "missing required key 'email'".to_string(),
+                "missing required key 'token'".to_string(),
+                "unexpected key 'debug'".to_string(),
+                "unexpected key 'name'".to_string(),
+            ])
+        );
+        assert_eq!(
+            request.check_root_shape(&["id", "email"], true),
+            Err(vec!["missing required key 'email'".to_string()])
+        );
+        assert_eq!(
+            value("[1]").check_root_shape(&[], true),
+            Err(vec!["expected an object, found array".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_pointer_array_indices() {
+        let mut v = value(r#"{"items": [10, 11], "01": "key"}"#);
+        assert_eq!(v.pointer("/items/0"), Some(&JsonValue::from(10)));
+        assert_eq!(v.pointer("/items/1"), Some(&JsonValue::from(11)));
+        for ptr in [
+            "/items/99999999999999999999",
+            "/items/18446744073709551616",
+            "/items/-",
+            "/items/01",
+            "/items/00",
+            "/items/+1",
+            "/items/",
+        ] {
+            assert_eq!(v.pointer(ptr), None, "{}", ptr);
+            assert_eq!(v.take_pointer(ptr), None, "{}", ptr);
+        }
+        // Leading zeros are only restricted for array indices, not keys
+        assert_eq!(
+            v.pointer("/01"),
+            Some(&JsonValue::String("key".to_string()))
+        );
+        assert!(v.query("/items/-").is_err());
+    }
+
+    #[test]
+    fn test_parse_embedded() {
+        let v = value(r#"{"meta": {"payload": "{\"user\": \"ann\", \"ids\": [1, 2]}"}}"#);
+        let embedded = v.parse_embedded("/meta/payload").unwrap();
+        assert_eq!(
+            embedded.pointer("/user"),
+            Some(&JsonValue::String("ann".to_string()))
+        );
+        assert_eq!(
+            embedded.pointer("/ids/1"),
+            Some(&JsonValue::Number(JsonNumber::I64(2)))
+        );
+
+        assert!(matches!(
+            v.parse_embedded("/meta"),
+            Err(JsonParseError::NoStringAtPointer(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge() {
+        let base =
+            value(r#"{"db": {"host": "localhost", "port": 5432}, "tags": ["a"], "debug": false}"#);
+        let overlay =
+            value(r#"{"db": {"port": 6543, "user": "app"}, "tags": ["b"], "debug": {"level": 2}}"#);
+
+        let mut replaced = base.clone();
+        replaced.merge(&overlay, MergeStrategy::Replace);
+        assert_eq!(
+            replaced,
+            value(
+                r#"{"db": {"host": "localhost", "port": 6543, "user": "app"}, "tags": ["b"], "debug": {"level": 2}}"#
+            )
+        );
+
+        let mut concatenated = base;
+        concatenated.merge(&overlay, MergeStrategy::Concat);
+        assert_eq!(concatenated.pointer("/tags"), Some(&value(r#"["a", "b"]"#)));
+
+        let mut scalar = value(r#"{"a": 1}"#);
+        scalar.merge(&JsonValue::Null, MergeStrategy::Replace);
+        assert_eq!(scalar, JsonValue::Null);
+    }
+
+    #[test]
+    fn test_apply_merge_patch() {
+        // The examples from RFC 7396, Appendix A, as [target, patch, result]
+        let examples = [
+            r#"[{"a": "b"}, {"a": "c"}, {"a": "c"}]"#,
+            r#"[{"a": "b"}, {"b": "c"}, {"a": "b", "b": "c"}]"#,
+            r#"[{"a": "b"}, {"a": null}, {}]"#,
+            r#"[{"a": "b", "b": "c"}, {"a": null}, {"b": "c"}]"#,
+            r#"[{"a": ["b"]}, {"a": "c"}, {"a": "c"}]"#,
+            r#"[{"a": "c"}, {"a": ["b"]}, {"a": ["b"]}]"#,
+            r#"[{"a": {"b": "c"}}, {"a": {"b": "d", "c": null}}, {"a": {"b": "d"}}]"#,
+            r#"[{"a": [{"b": "c"}]}, {"a": [1]}, {"a": [1]}]"#,
+            r#"[["a", "b"], ["c", "d"], ["c", "d"]]"#,
+            r#"[{"a": "b"}, ["c"], ["c"]]"#,
+            r#"[{"a": "foo"}, null, null]"#,
+            r#"[{"a": "foo"}, "bar", "bar"]"#,
+            r#"[{"e": null}, {"a": 1}, {"e": null, "a": 1}]"#,
+            r#"[[1, 2], {"a": "b", "c": null}, {"a": "b"}]"#,
+            r#"[{}, {"a": {"bb": {"ccc": null}}}, {"a": {"bb": {}}}]"#,
+        ];
+        for example in examples {
+            let v = value(example);
+            let mut target = v.pointer("/0").unwrap().clone();
+            target.apply_merge_patch(v.pointer("/1").unwrap());
+            assert!(target.eq_ordered(v.pointer("/2").unwrap()), "{}", example);
+        }
+
+        let mut repeated = value(r#"{"a": 1, "b": 2, "a": 3}"#);
+        repeated.apply_merge_patch(&value(r#"{"a": null}"#));
+        assert_eq!(repeated.to_string(), r#"{"b":2}"#);
+
+        let options = ParseOptions {
+            object_backing: ObjectBacking::Map,
+            ..ParseOptions::default()
+        };
+        let mut map = JsonDocument::parse(r#"{"a": 1, "b": {"c": 2}}"#)
+            .unwrap()
+            .to_value_with_options(&options)
+            .unwrap();
+        map.apply_merge_patch(&value(r#"{"a": null, "b": {"d": 3}}"#));
+        assert_eq!(map.to_string(), r#"{"b":{"c":2,"d":3}}"#);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let v = value(
+            r#"[
+                {"name": "apple", "category": "fruit"},
+                {"name": "carrot", "category": "vegetable"},
+                {"name": "pear", "category": "fruit"},
+                {"name": "rock"},
+                42
+            ]"#,
+        );
+
+        let groups = v.group_by("category").unwrap();
+        assert_eq!(
+            groups.keys().collect::<Vec<_>>(),
+            ["fruit", "unknown", "vegetable"]
+        );
+        let fruit: Vec<_> = groups["fruit"]
+            .iter()
+            .map(|item| item.pointer("/name"))
+            .collect();
+        assert_eq!(
+            fruit,
+            [
+                Some(&JsonValue::String("apple".to_string())),
+                Some(&JsonValue::String("pear".to_string()))
+            ]
+        );
+        assert_eq!(groups["unknown"].len(), 2);
+
+        let err = v.group_by_with("category", true).unwrap_err();
+        assert!(err.contains("element 3"));
+        assert!(value("{}").group_by("category").is_err());
+    }
+
+    #[test]
+    fn test_index_by() {
+        let v = value(r#"[{"id": "a", "n": 1}, {"id": "b", "n": 2}]"#);
+        assert_eq!(
+            v.index_by("id").unwrap(),
+            value(r#"{"a": {"id": "a", "n": 1}, "b": {"id": "b", "n": 2}}"#)
+        );
+
+        let duplicated = value(r#"[{"id": "a", "n": 1}, {"id": "b"}, {"id": "a", "n": 3}]"#);
+        assert!(duplicated.index_by("id").unwrap_err().contains("duplicate"));
+        assert_eq!(
+            duplicated.index_by_with("id", true).unwrap(),
+            value(r#"{"a": {"id": "a", "n": 3}, "b": {"id": "b"}}"#)
+        );
+
+        assert!(value(r#"[{"id": 1}]"#).index_by("id").is_err());
+    }
+
+    #[test]
+    fn test_has_pointer_and_contains_key() {
+        let v = value(r#"{"a": null, "b": {"c": [null]}}"#);
+
+        assert!(v.contains_key("a"));
+        assert!(!v.contains_key("missing"));
+        assert!(!v.contains_key("c"));
+        assert!(!value("[1]").contains_key("0"));
+
+        assert!(v.has_pointer("/a"));
+        assert!(v.has_pointer("/b/c/0"));
+        assert!(v.has_pointer(""));
+        assert!(!v.has_pointer("/b/c/1"));
+        assert!(!v.has_pointer("/missing"));
+    }
+
+    #[test]
+    fn test_entries_and_elements() {
+        let v = value(r#"{"z": [3, {"b": 1, "a": 2}, "x"], "m": null, "a": []}"#);
+
+        let keys: Vec<&str> = v.entries().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["z", "m", "a"]);
+
+        let mut strings = Vec::new();
+        for (_, member) in v.entries() {
+            for item in member.elements() {
+                for (key, _) in item.entries() {
+                    strings.push(key.to_string());
+                }
+                if let JsonValue::String(s) = item {
+                    strings.push(s.clone());
+                }
+            }
+        }
+        assert_eq!(strings, ["b", "a", "x"]);
+
+        let items: Vec<String> = v
+            .get("z")
+            .unwrap()
+            .elements()
+            .map(|i| i.to_string())
+            .collect();
+        assert_eq!(items, ["3", r#"{"b":1,"a":2}"#, r#""x""#]);
+
+        assert_eq!(v.elements().count(), 0);
+        assert_eq!(v.get("z").unwrap().entries().count(), 0);
+        assert_eq!(JsonValue::Null.entries().count(), 0);
+    }
+
+    #[test]
+    fn test_take_pointer() {
+        let mut v = value(r#"{"a": {"big": [1, 2, 3], "keep": true}, "list": ["x", "y", "z"]}"#);
+
+        assert_eq!(v.take_pointer("/a/big"), Some(value("[1, 2, 3]")));
+        assert_eq!(
+            v.take_pointer("/list/0"),
+            Some(JsonValue::String("x".to_string()))
+        );
+        assert_eq!(v, value(r#"{"a": {"keep": true}, "list": ["y", "z"]}"#));
+
+        assert_eq!(v.take_pointer("/list/2"), None);
+        assert_eq!(v.take_pointer("/missing/key"), None);
+        assert_eq!(v.take_pointer("/a/keep/x"), None);
+
+        assert_eq!(
+            v.take_pointer(""),
+            Some(value(r#"{"a": {"keep": true}, "list": ["y", "z"]}"#))
+        );
+        assert_eq!(v, JsonValue::Null);
+    }
+
+    #[test]
+    fn test_clone_pointer() {
+        let v = value(r#"{"rows": [[1, 2], [3, {"x": [4]}]]}"#);
+
+        let mut row = v.clone_pointer("/rows/1").unwrap();
+        assert_eq!(row, value(r#"[3, {"x": [4]}]"#));
+        if let JsonValue::Array(items) = &mut row {
+            if let Some(JsonValue::Array(xs)) = items[1].get_mut("x") {
+                xs.push(JsonValue::Null);
+            }
+            items[0] = JsonValue::Bool(true);
+        }
+
+        assert_eq!(row, value(r#"[true, {"x": [4, null]}]"#));
+        assert_eq!(v, value(r#"{"rows": [[1, 2], [3, {"x": [4]}]]}"#));
+        assert_eq!(v.clone_pointer(""), Some(v.clone()));
+        assert_eq!(v.clone_pointer("/rows/2"), None);
+    }
+
+    #[test]
+    fn test_duplicate_key_policy() {
+        let build = |policy| {
+            let options = ParseOptions {
+                duplicate_keys: policy,
+                ..ParseOptions::default()
+            };
+            JsonDocument::parse(r#"{"a":1,"b":0,"a":2}"#)
+                .unwrap()
+                .to_value_with_options(&options)
+        };
+
+        assert!(matches!(
+            build(DuplicateKeyPolicy::Error),
+            Err(JsonParseError::DuplicateKey { key }) if key == "a"
+        ));
+        assert_eq!(
+            build(DuplicateKeyPolicy::KeepFirst).unwrap().to_string(),
+            r#"{"a":1,"b":0}"#
+        );
+        assert_eq!(
+            build(DuplicateKeyPolicy::KeepLast).unwrap().to_string(),
+            r#"{"a":2,"b":0}"#
+        );
+        assert_eq!(
+            build(DuplicateKeyPolicy::KeepAll).unwrap().to_string(),
+            r#"{"a":1,"b":0,"a":2}"#
+        );
+    }
+
+    #[test]
+    fn test_map_strings() {
+        let mut v = value(r#"{"Name": "Ann", "Tags": [{"KIND": "A"}], "N": 1}"#);
+        v.map_strings(|s| s.to_lowercase());
+        assert_eq!(
+            v,
+            value(r#"{"name": "ann", "tags": [{"kind": "a"}], "n": 1}"#)
+        );
+
+        let options = ParseOptions {
+            object_backing: ObjectBacking::Map,
+            ..ParseOptions::default()
+        };
+        let mut map = JsonDocument::parse(r#"{"Outer": {"Inner": "X"}}"#)
+            .unwrap()
+            .to_value_with_options(&options)
+            .unwrap();
+        map.map_strings(|s| s.to_lowercase());
+        assert_eq!(map, value(r#"{"outer": {"inner": "x"}}"#));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut v = value(
+            r#"{"_id": 1, "name": "x", "items": [{"_tmp": true, "k": [{"_a": 1, "b": 2}]}, 3],
+                "nested": {"_drop": {"keep": 1}, "keep": {"_x": null, "y": []}}}"#,
+        );
+        v.retain(|key, _| !key.starts_with('_'));
+        assert_eq!(
+            v,
+            value(
+                r#"{"name": "x", "items": [{"k": [{"b": 2}]}, 3],
+                    "nested": {"keep": {"y": []}}}"#
+            )
+        );
+
+        let mut v = value(r#"[1, null, {"a": [null, 2, [null]]}]"#);
+        v.retain_elements(|item| *item != JsonValue::Null);
+        assert_eq!(v, value(r#"[1, {"a": [2, []]}]"#));
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut v = value(r#"{"b": {"z": 1, "a": [{"y": 2, "x": 3}]}, "a": null, "B": 0}"#);
+        v.sort_keys();
+        assert_eq!(
+            v.to_string(),
+            r#"{"B":0,"a":null,"b":{"a":[{"x":3,"y":2}],"z":1}}"#
+        );
+
+        let once = v.to_string();
+        v.sort_keys();
+        assert_eq!(v.to_string(), once);
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let a = value(
+            "{\r\n  \"b\": [1.0, -0, 2.5e0],\r\n  \"a\": {\"y\": 1e2, \"x\": \"\\u00e9\"}\r\n}",
+        );
+        let b = value("{\"a\":{\"x\":\"\u{e9}\",\"y\":100},\n\t\"b\":[1,0,2.5]}");
+
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        assert_eq!(
+            a.canonical_bytes(),
+            "{\"a\":{\"x\":\"\u{e9}\",\"y\":100},\"b\":[1,0,2.5]}".as_bytes()
+        );
+
+        let mut canonical = a.clone();
+        canonical.canonicalize();
+        assert_eq!(canonical.to_string().into_bytes(), a.canonical_bytes());
+    }
+
+    #[test]
+    fn test_all_keys() {
+        let v = value(r#"{"b": [{"a": 1}, {"c": {"a": 2}}], "d": [[], "x"]}"#);
+        let keys: Vec<String> = v.all_keys().into_iter().collect();
+        assert_eq!(keys, ["a", "b", "c", "d"]);
+        assert!(value("[1, [2]]").all_keys().is_empty());
+    }
+
+    #[test]
+    fn test_walk_stops_early() {
+        let v = value(r#"{"a": [1, "first", {"b": "second"}], "c": null}"#);
+
+        let mut visited = 0;
+        let mut found = None;
+        let flow = v.walk(|node| {
+            visited += 1;
+            match node {
+                JsonValue::String(s) => {
+                    found = Some(s.clone());
+                    ControlFlow::Break(())
+                }
+                _ => ControlFlow::Continue(()),
+            }
+        });
+
+        assert_eq!(flow, ControlFlow::Break(()));
+        assert_eq!(found.as_deref(), Some("first"));
+        assert_eq!(visited, 4);
+
+        let mut total = 0;
+        assert_eq!(
+            v.walk(|_| {
+                total += 1;
+                ControlFlow::Continue(())
+            }),
+            ControlFlow::Continue(())
+        );
+        assert_eq!(total, 7);
+    }
+
+    #[test]
+    fn test_find_paths() {
+        let v =
+            value(r#"{"a": null, "b": [1, null, {"c/d": null, "e": "TODO"}], "f": {"g": [null]}}"#);
+
+        assert_eq!(
+            v.find_paths(|value| *value == JsonValue::Null),
+            ["/a", "/b/1", "/b/2/c~1d", "/f/g/0"]
+        );
+        assert_eq!(
+            v.find_paths(|value| matches!(value, JsonValue::String(s) if s == "TODO")),
+            ["/b/2/e"]
+        );
+        assert_eq!(v.find_paths(|value| value.depth() == 3), [""]);
+        assert!(v
+            .find_paths(|value| *value == JsonValue::Bool(true))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_estimated_size() {
+        let empty = value("{}");
+        let nested = value(r#"{"name": "a longer string value", "list": [1, 2, {"k": null}]}"#);
+
+        assert_eq!(empty.estimated_size(), mem::size_of::<JsonValue>());
+        assert!(nested.estimated_size() > empty.estimated_size());
+        assert!(nested.estimated_size() > "a longer string value".len());
+    }
+
+    #[test]
+    fn test_walk_mut() {
+        let mut v = value(
+            r#"{"user": {"password": "secret", "name": "x"},
+                "raw": {"token": "secret"},
+                "list": ["secret", 1, {"k": "secret"}],
+                "drop": {"deep": {"deeper": 1}}}"#,
+        );
+        let mut visited = Vec::new();
+        v.walk_mut(|path, node| {
+            visited.push(path.to_string());
+            match node {
+                JsonValue::String(s) if s == "secret" => *s = "***".to_string(),
+                _ if path == "/raw" => return WalkAction::SkipChildren,
+                _ if path == "/drop" => return WalkAction::Replace(JsonValue::Null),
+                _ => {}
+            }
+            WalkAction::Continue
+        });
+
+        assert_eq!(
+            v,
+            value(
+                r#"{"user": {"password": "***", "name": "x"},
+                    "raw": {"token": "secret"},
+                    "list": ["***", 1, {"k": "***"}],
+                    "drop": null}"#
+            )
+        );
+        assert!(!visited.iter().any(|path| path.starts_with("/raw/")));
+        assert!(!visited.iter().any(|path| path.starts_with("/drop/")));
+        assert_eq!(visited[0], "");
+        assert!(visited.contains(&"/list/2/k".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_normalize_nfc() {
+        // "café" with a precomposed é, then with e and a combining acute accent
+        let input = r#"{"caf\u00e9": "caf\u00e9", "cafe\u0301": "cafe\u0301"}"#;
+        let doc = JsonDocument::parse(input).unwrap();
+        let nfc = ParseOptions {
+            normalize_nfc: true,
+            ..ParseOptions::default()
+        };
+
+        let plain = doc.to_value().unwrap();
+        let keys: Vec<&str> = plain.entries().map(|(key, _)| key).collect();
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(plain.get(keys[0]), plain.get(keys[1]));
+
+        let normalized = doc.to_value_with_options(&nfc).unwrap();
+        let members: Vec<(&str, &JsonValue)> = normalized.entries().collect();
+        assert_eq!(members[0], members[1]);
+        assert_eq!(members[0].0, "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_eq_ordered() {
+        let a = value(r#"{"id": 1, "tags": [{"x": 1, "y": 2}], "name": "a"}"#);
+        let b = value(r#"{"name": "a", "id": 1.0, "tags": [{"x": 1, "y": 2}]}"#);
+        let c = value(r#"{"id": 1, "tags": [{"y": 2, "x": 1}], "name": "a"}"#);
+
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert!(!a.eq_ordered(&b));
+        assert!(!a.eq_ordered(&c));
+        assert!(a.eq_ordered(&a.clone()));
+        assert!(a.eq_ordered(&value(
+            r#"{"id": 1e0, "tags": [{"x": 1, "y": 2}], "name": "a"}"#
+        )));
+
+        assert_eq!(value(r#"{"k": 1, "k": 2}"#), value(r#"{"k": 1, "k": 2}"#));
+        assert_ne!(value(r#"{"k": 1, "k": 2}"#), value(r#"{"k": 2, "k": 1}"#));
+        assert_ne!(value(r#"{"a": 1}"#), value(r#"{"a": 1, "b": 2}"#));
+
+        let options = ParseOptions {
+            object_backing: ObjectBacking::Map,
+            ..ParseOptions::default()
+        };
+        let map = |json: &str| {
+            JsonDocument::parse(json)
+                .unwrap()
+                .to_value_with_options(&options)
+                .unwrap()
+        };
+        assert!(map(r#"{"b": 1, "a": 2}"#).eq_ordered(&value(r#"{"a": 2, "b": 1}"#)));
+        assert!(!map(r#"{"b": 1, "a": 2}"#).eq_ordered(&value(r#"{"b": 1, "a": 2}"#)));
+    }
+
+    #[test]
+    fn test_len_and_is_empty() {
+        let v = value(r#"[{}, {"a": 1, "a": 2}, [], [null, []], "", "héllo", null, false, 0]"#);
+        let lens: Vec<usize> = v.elements().map(JsonValue::len).collect();
+        let empty: Vec<bool> = v.elements().map(JsonValue::is_empty).collect();
+
+        assert_eq!(lens, [0, 2, 0, 2, 0, 5, 0, 0, 0]);
+        assert_eq!(
+            empty,
+            [true, false, true, false, true, false, true, false, false]
+        );
+
+        let options = ParseOptions {
+            object_backing: ObjectBacking::Map,
+            ..ParseOptions::default()
+        };
+        let map = JsonDocument::parse(r#"{"a": 1, "a": 2, "b": 3}"#)
+            .unwrap()
+            .to_value_with_options(&options)
+            .unwrap();
+        assert_eq!(map.len(), 2);
+        assert!(!map.is_empty());
+    }
+
+    #[test]
+    fn test_coerced_accessors() {
+        let v = value(
+            r#"[true, "true", "false", "True", " true", 42, "42", "-7", "1e3", "4.5", "+1", " 1",
+                "0x2A", "9223372036854775808", "2.5", "-1e-3", "1e400", "NaN", null]"#,
+        );
+        let item = |index: usize| v.pointer(&format!("/{}", index)).unwrap();
+
+        assert_eq!(item(0).as_bool_coerced(), Some(true));
+        assert_eq!(item(1).as_bool_coerced(), Some(true));
+        assert_eq!(item(2).as_bool_coerced(), Some(false));
+        assert_eq!(item(3).as_bool_coerced(), None);
+        assert_eq!(item(4).as_bool_coerced(), None);
+        assert_eq!(item(1).as_bool(), None);
+
+        assert_eq!(item(5).as_i64_coerced(), Some(42));
+        assert_eq!(item(6).as_i64_coerced(), Some(42));
+        assert_eq!(item(7).as_i64_coerced(), Some(-7));
+        assert_eq!(item(8).as_i64_coerced(), Some(1000));
+        for index in [9, 10, 11, 12, 13, 18] {
+            assert_eq!(item(index).as_i64_coerced(), None, "item {}", index);
+        }
+        assert_eq!(item(6).as_i64(), None);
+
+        assert_eq!(item(14).as_f64_coerced(), Some(2.5));
+        assert_eq!(item(15).as_f64_coerced(), Some(-0.001));
+        assert_eq!(item(5).as_f64_coerced(), Some(42.0));
+        for index in [10, 16, 17, 18] {
+            assert_eq!(item(index).as_f64_coerced(), None, "item {}", index);
+        }
+    }
+
+    #[test]
+    fn test_key_frequency() {
+        let v = value(
+            r#"{"id": 1, "items": [{"id": 2, "name": "a"}, {"name": "b", "meta": {"id": 3}}]}"#,
+        );
+        assert_eq!(v.key_frequency("id"), 3);
+        assert_eq!(v.key_frequency("name"), 2);
+        assert_eq!(v.key_frequency("missing"), 0);
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            value(r#"{"a": 1, "b": {"c": true}, "d": "x"}"#).summary(),
+            "object{3 keys, depth 2}"
+        );
+        assert_eq!(
+            value("[1, 2, 3, 4, 5]").summary(),
+            "array[5 items, depth 1]"
+        );
+        assert_eq!(value(r#"[["x"]]"#).summary(), "array[1 item, depth 2]");
+        assert_eq!(
+            JsonValue::String("héllo".to_string()).summary(),
+            "string(5 chars)"
+        );
+    }
+
+    #[test]
+    fn test_escaped_forward_slash() {
+        assert_eq!(
+            value(r#"["a\/b", "a/b"]"#),
+            JsonValue::Array(vec![
+                JsonValue::String("a/b".to_string()),
+                JsonValue::String("a/b".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_surrogate_pair() {
+        assert_eq!(
+            value(r#"["\uD83D\uDE00 and \u00e9"]"#),
+            JsonValue::Array(vec![JsonValue::String("😀 and é".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_reversed_surrogate_pair() {
+        let doc = JsonDocument::parse(r#"["\uDE00\uD83D"]"#).unwrap();
+        assert!(matches!(
+            doc.to_value(),
+            Err(JsonParseError::UnpairedSurrogate {
+                code: 0xDE00,
+                line: 1,
+                column: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_lone_high_surrogate() {
+        let doc = JsonDocument::parse(r#"{"key": "a\uD83Db"}"#).unwrap();
+        assert!(matches!(
+            doc.to_value(),
+            Err(JsonParseError::UnpairedSurrogate {
+                code: 0xD83D,
+                line: 1,
+                column: 11
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unflatten() {
+        let flat = value(r#"{"a.b.c": 1, "a.b.d": 2, "a.e": true, "f": null}"#);
+        assert_eq!(
+            JsonValue::unflatten(&flat, '.').unwrap(),
+            value(r#"{"a": {"b": {"c": 1, "d": 2}, "e": true}, "f": null}"#)
+        );
+
+        let conflicting = value(r#"{"a": 1, "a.b": 2}"#);
+        assert!(JsonValue::unflatten(&conflicting, '.').is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! # Borrowed JSON Values
+//!
+//! This module provides a value tree that borrows strings and keys from the
+//! source text instead of copying them.
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::vec::Vec;
+
+use pest::iterators::Pair;
+
+use crate::value::decode_string;
+use crate::{JsonNumber, JsonParseError, JsonValue, Rule};
+
+/// Represents a single JSON value borrowing from the document it was parsed from.
+///
+/// Strings and keys without escape sequences borrow directly from the input;
+/// only those containing escapes are decoded into owned strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValueRef<'a> {
+    /// The `null` literal
+    Null,
+    /// A `true` or `false` literal
+    Bool(bool),
+    /// A number, with integers kept exact where they fit in 64 bits
+    Number(JsonNumber),
+    /// A string, borrowed unless it contained escapes
+    String(Cow<'a, str>),
+    /// An array of values
+    Array(Vec<JsonValueRef<'a>>),
+    /// An object, with members kept in source order
+    Object(Vec<(Cow<'a, str>, JsonValueRef<'a>)>),
+}
+
+impl<'a> JsonValueRef<'a> {
+    /// Builds a `JsonValueRef` from a pest pair matched by one of the value rules.
+    pub(crate) fn from_pair(pair: Pair<'a, Rule>) -> Result<Self, JsonParseError> {
+        match pair.as_rule() {
+            Rule::null => Ok(JsonValueRef::Null),
+            Rule::boolean => Ok(JsonValueRef::Bool(pair.as_str() == "true")),
+            Rule::number => Ok(JsonValueRef::Number(JsonNumber::from_token(pair.as_str()))),
+            Rule::js_number => Ok(JsonValueRef::Number(JsonNumber::from_js_token(
+                pair.as_str(),
+            ))),
+            Rule::string => Ok(JsonValueRef::String(decode_string(pair)?)),
+            Rule::array => pair
+                .into_inner()
+                .map(JsonValueRef::from_pair)
+                .collect::<Result<Vec<_>, _>>()
+                .map(JsonValueRef::Array),
+            Rule::object => pair
+                .into_inner()
+                .map(|member| {
+                    let mut inner = member.into_inner();
+                    let key = inner.next().ok_or(JsonParseError::EmptyJson)?;
+                    let value = inner.next().ok_or(JsonParseError::EmptyJson)?;
+                    Ok((decode_string(key)?, JsonValueRef::from_pair(value)?))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(JsonValueRef::Object),
+            rule => Err(JsonParseError::UnexpectedRootType(format!("{:?}", rule))),
+        }
+    }
+
+    /// Looks up a member of an object by key, with the last duplicate winning.
+    pub fn get(&self, key: &str) -> Option<&JsonValueRef<'a>> {
+        match self {
+            JsonValueRef::Object(members) => {
+                members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the string contents if this is a string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValueRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<JsonValueRef<'_>> for JsonValue {
+    /// Converts a borrowed value into an owned one, copying any borrowed strings.
+    fn from(value: JsonValueRef<'_>) -> Self {
+        match value {
+            JsonValueRef::Null => JsonValue::Null,
+            JsonValueRef::Bool(b) => JsonValue::Bool(b),
+            JsonValueRef::Number(n) => JsonValue::Number(n),
+            JsonValueRef::String(s) => JsonValue::String(s.into_owned()),
+            JsonValueRef::Array(items) => {
+                JsonValue::Array(items.into_iter().map(JsonValue::from).collect())
+            }
+            JsonValueRef::Object(members) => JsonValue::Object(
+                members
+                    .into_iter()
+                    .map(|(k, v)| (k.into_owned(), JsonValue::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonDocument;
+
+    #[test]
+    fn test_borrows_without_escapes() {
+        let doc = JsonDocument::parse(r#"{"name": "plain", "tags": ["a", "b"]}"#).unwrap();
+        let value = doc.to_value_ref().unwrap();
+
+        let JsonValueRef::Object(members) = &value else {
+            panic!("expected object");
+        };
+        assert!(matches!(members[0].0, Cow::Borrowed("name")));
+        assert!(matches!(
+            value.get("name"),
+            Some(JsonValueRef::String(Cow::Borrowed("plain")))
+        ));
+    }
+
+    #[test]
+    fn test_allocates_for_escapes() {
+        let doc = JsonDocument::parse(r#"{"k\u0065y": "line\nbreak"}"#).unwrap();
+        let value = doc.to_value_ref().unwrap();
+
+        let JsonValueRef::Object(members) = &value else {
+            panic!("expected object");
+        };
+        assert!(matches!(&members[0].0, Cow::Owned(key) if key == "key"));
+        assert!(matches!(
+            value.get("key"),
+            Some(JsonValueRef::String(Cow::Owned(s))) if s == "line\nbreak"
+        ));
+        assert_eq!(JsonValue::from(value), doc.to_value().unwrap());
+    }
+}
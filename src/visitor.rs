@@ -0,0 +1,90 @@
+//! # Event Visitor
+//!
+//! This module provides a SAX-style interface that reports a document as a
+//! sequence of callbacks instead of building a `JsonValue` tree.
+
+use alloc::format;
+
+use pest::iterators::Pair;
+
+use crate::value::decode_string;
+use crate::{JsonParseError, Rule};
+
+/// Receives parse events in document order from `JsonDocument::visit`.
+///
+/// Every method has an empty default, so a visitor only implements the
+/// events it cares about. Strings and keys arrive with escapes decoded;
+/// numbers arrive as their source text.
+pub trait JsonVisitor {
+    /// Called when an object opens, before its first key.
+    fn start_object(&mut self) {}
+    /// Called when an object closes.
+    fn end_object(&mut self) {}
+    /// Called when an array opens, before its first element.
+    fn start_array(&mut self) {}
+    /// Called when an array closes.
+    fn end_array(&mut self) {}
+    /// Called with each object key, just before its value.
+    fn key(&mut self, _key: &str) {}
+    /// Called with a string value.
+    fn string(&mut self, _value: &str) {}
+    /// Called with the source text of a number.
+    fn number(&mut self, _value: &str) {}
+    /// Called with a boolean value.
+    fn boolean(&mut self, _value: bool) {}
+    /// Called for a `null` value.
+    fn null(&mut self) {}
+}
+
+/// Dispatches the events for `pair` and its descendants to `visitor`.
+///
+/// Every string is checked first, so an invalid escape anywhere in the
+/// document fails before the visitor sees a single event.
+pub(crate) fn dispatch<V: JsonVisitor>(
+    pair: Pair<Rule>,
+    visitor: &mut V,
+) -> Result<(), JsonParseError> {
+    check_strings(&pair)?;
+    dispatch_events(pair, visitor)
+}
+
+/// Decodes every string under `pair`, keys included, that contains a `\u`
+/// escape; those are the only strings whose decoding can fail.
+fn check_strings(pair: &Pair<Rule>) -> Result<(), JsonParseError> {
+    core::iter::once(pair.clone())
+        .chain(pair.clone().into_inner().flatten())
+        .filter(|p| p.as_rule() == Rule::string && p.as_str().contains("\\u"))
+        .try_for_each(|p| decode_string(p).map(drop))
+}
+
+fn dispatch_events<V: JsonVisitor>(
+    pair: Pair<Rule>,
+    visitor: &mut V,
+) -> Result<(), JsonParseError> {
+    match pair.as_rule() {
+        Rule::object => {
+            visitor.start_object();
+            for member in pair.into_inner() {
+                let mut inner = member.into_inner();
+                let key = inner.next().ok_or(JsonParseError::EmptyJson)?;
+                visitor.key(&decode_string(key)?);
+                let value = inner.next().ok_or(JsonParseError::EmptyJson)?;
+                dispatch_events(value, visitor)?;
+            }
+            visitor.end_object();
+        }
+        Rule::array => {
+            visitor.start_array();
+            for item in pair.into_inner() {
+                dispatch_events(item, visitor)?;
+            }
+            visitor.end_array();
+        }
+        Rule::string => visitor.string(&decode_string(pair)?),
+        Rule::number => visitor.number(pair.as_str()),
+        Rule::boolean => visitor.boolean(pair.as_str() == "true"),
+        Rule::null => visitor.null(),
+        rule => return Err(JsonParseError::UnexpectedRootType(format!("{:?}", rule))),
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn run_with_stdin(args: &[&str], stdin: &str) -> String {
+    run_with_stdin_status(args, stdin).0
+}
+
+// Runs the binary with `stdin`, returning its stdout and whether it exited successfully
+fn run_with_stdin_status(args: &[&str], stdin: &str) -> (String, bool) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_json_parser"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start binary");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin.as_bytes())
+        .expect("failed to write stdin");
+
+    let output = child.wait_with_output().expect("failed to wait for binary");
+    let stdout = String::from_utf8(output.stdout).expect("stdout is UTF-8");
+    (stdout, output.status.success())
+}
+
+// Runs the binary with `args`, returning whether it exited successfully
+fn run_status(args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_json_parser"))
+        .args(args)
+        .output()
+        .expect("failed to run binary")
+        .status
+        .success()
+}
+
+// Writes `content` to a fresh file in the system temp directory
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("json_parser_{}_{}.json", std::process::id(), name));
+    fs::write(&path, content).expect("failed to write temp file");
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test validating a document piped through the `-` argument
+    #[test]
+    fn test_stdin_dash_argument() {
+        let stdout = run_with_stdin(&["-"], "{\"key\": [1, 2, 3]}\n");
+        assert!(stdout.contains("JSON is valid!"), "{}", stdout);
+        assert!(stdout.contains("File: <stdin>"), "{}", stdout);
+        assert!(stdout.contains("Root Type: Object"), "{}", stdout);
+    }
+
+    // Test that piped input is read when no file path is given
+    #[test]
+    fn test_stdin_without_arguments() {
+        let (stdout, success) = run_with_stdin_status(&[], "[1, 2,]");
+        assert!(stdout.contains("JSON is invalid!"), "{}", stdout);
+        assert!(stdout.contains("File: <stdin>"), "{}", stdout);
+        assert!(!success);
+
+        assert!(run_with_stdin_status(&[], "[1, 2]").1);
+        assert!(!run_with_stdin_status(&["-"], "{").1);
+    }
+
+    // Test that errors show the offending line with a caret, with tabs expanded
+    #[test]
+    fn test_caret_diagnostic() {
+        let stdout = run_with_stdin(&["-"], "\n{\n\t\"a\": 1\n\t\"b\": 2\n}");
+        assert!(stdout.contains("4 |     \"b\": 2\n  |     ^\n"), "{}", stdout);
+
+        let path = temp_file("caret", "[1, 2,]");
+        let stdout = run_with_stdin(&[path.to_str().unwrap()], "");
+        assert!(stdout.contains("1 | [1, 2,]\n  |       ^\n"), "{}", stdout);
+        fs::remove_file(path).unwrap();
+
+        // The message and the gutter agree on the line, and a BOM does not shift the caret
+        let stdout = run_with_stdin(&["-"], "\n\n{\n    \"a\": 1,\n    \"b\" 2}");
+        assert!(stdout.contains("at line 5, column 9\n"), "{}", stdout);
+        assert!(stdout.contains("5 |     \"b\" 2}\n  |         ^\n"), "{}", stdout);
+
+        let path = temp_file("caret_bom", "\u{feff}[1, 2,]");
+        let stdout = run_with_stdin(&[path.to_str().unwrap()], "");
+        assert!(stdout.contains("at line 1, column 7\n"), "{}", stdout);
+        assert!(stdout.contains("1 | [1, 2,]\n  |       ^\n"), "{}", stdout);
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that piped output is never colored and --no-color is not taken as a path
+    #[test]
+    fn test_no_color() {
+        let stdout = run_with_stdin(&["-"], "[1, 2,]");
+        assert!(!stdout.contains('\x1b'), "{}", stdout);
+
+        let stdout = run_with_stdin(&["--no-color", "-"], "[1 2]");
+        assert!(stdout.contains("JSON is invalid!"), "{}", stdout);
+        assert!(stdout.contains("1 | [1 2]\n  |    ^\n"), "{}", stdout);
+        assert!(!stdout.contains('\x1b'), "{}", stdout);
+
+        let path = temp_file("no_color", "{}");
+        let stdout = run_with_stdin(&[path.to_str().unwrap(), "--no-color"], "");
+        assert!(stdout.contains("JSON is valid!"), "{}", stdout);
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that --stats prints the shape of a known fixture
+    #[test]
+    fn test_stats() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/config.json");
+        let stdout = run_with_stdin(&[fixture, "--stats"], "");
+        assert_eq!(
+            stdout,
+            "root: object\ndepth: 2\nobjects: 1\narrays: 1\nstrings: 1\nnumbers: 2\n\
+             booleans: 1\nnulls: 0\nkeys: 3\nbytes: 69\n"
+        );
+
+        let path = temp_file("stats_invalid", "{\"a\": [1, 2,]}");
+        assert!(!run_status(&[path.to_str().unwrap(), "--stats"]));
+        let stdout = run_with_stdin(&[path.to_str().unwrap(), "--stats"], "");
+        assert!(stdout.contains("JSON is invalid!"), "{}", stdout);
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that invalid and missing files exit non-zero with or without --stats
+    #[test]
+    fn test_exit_status() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/config.json");
+        assert!(run_status(&[fixture]));
+
+        let path = temp_file("exit_invalid", "[1, 2,]");
+        assert!(!run_status(&[path.to_str().unwrap()]));
+        fs::remove_file(path).unwrap();
+
+        assert!(!run_status(&["/nonexistent/file.json"]));
+        assert!(!run_status(&["/nonexistent/file.json", "--stats"]));
+    }
+
+    // Test that fmt rewrites a file with 2-space indentation
+    #[test]
+    fn test_fmt_rewrites_file() {
+        let path = temp_file("fmt_rewrite", "{\"a\":[1,2],  \"b\": {}}");
+        assert!(run_status(&["fmt", path.to_str().unwrap()]));
+        let formatted = fs::read_to_string(&path).unwrap();
+        assert_eq!(formatted, "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}\n");
+
+        // Formatting is idempotent and --check now passes
+        assert!(run_status(&["fmt", "--check", path.to_str().unwrap()]));
+        assert!(run_status(&["fmt", path.to_str().unwrap()]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), formatted);
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that fmt keeps number tokens exactly as written
+    #[test]
+    fn test_fmt_preserves_numbers() {
+        let path = temp_file("fmt_numbers", "{\"big\": 12345678901234567890123, \"f\": 1.0, \"e\": 1E2}");
+        assert!(run_status(&["fmt", path.to_str().unwrap()]));
+        let formatted = fs::read_to_string(&path).unwrap();
+        assert_eq!(formatted, "{\n  \"big\": 12345678901234567890123,\n  \"f\": 1.0,\n  \"e\": 1E2\n}\n");
+        assert!(run_status(&["fmt", "--check", path.to_str().unwrap()]));
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that fmt reads files the way validation does, dropping a byte order mark
+    #[test]
+    fn test_fmt_bom_file() {
+        let path = temp_file("fmt_bom", "\u{feff}[1,  2]");
+        assert!(run_status(&[path.to_str().unwrap()]));
+        assert!(!run_status(&["fmt", "--check", path.to_str().unwrap()]));
+        assert!(run_status(&["fmt", path.to_str().unwrap()]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[\n  1,\n  2\n]\n");
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that fmt --check fails without modifying an unformatted file
+    #[test]
+    fn test_fmt_check_leaves_file_untouched() {
+        let original = "[1,2]";
+        let path = temp_file("fmt_check", original);
+        assert!(!run_status(&["fmt", "--check", path.to_str().unwrap()]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        fs::remove_file(path).unwrap();
+    }
+
+    // Test that fmt refuses to touch invalid JSON
+    #[test]
+    fn test_fmt_invalid_json() {
+        let original = "{\"a\": [1, 2,]}";
+        let path = temp_file("fmt_invalid", original);
+        assert!(!run_status(&["fmt", path.to_str().unwrap()]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        fs::remove_file(path).unwrap();
+    }
+}
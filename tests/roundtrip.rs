@@ -0,0 +1,70 @@
+use json_parser::{JsonDocument, JsonNumber, JsonRootType, JsonValue, ParseOptions};
+use proptest::prelude::*;
+
+// Numbers as the parser produces them: exact integers and finite floats
+fn arb_number() -> impl Strategy<Value = JsonNumber> {
+    prop_oneof![
+        any::<i64>().prop_map(JsonNumber::I64),
+        (i64::MAX as u64 + 1..=u64::MAX).prop_map(JsonNumber::U64),
+        any::<f64>()
+            .prop_filter("JSON has no NaN or infinity", |n| n.is_finite())
+            .prop_map(JsonNumber::F64),
+    ]
+}
+
+// Random value trees, with strings drawn from all of Unicode including control characters
+fn arb_value() -> impl Strategy<Value = JsonValue> {
+    let leaf = prop_oneof![
+        Just(JsonValue::Null),
+        any::<bool>().prop_map(JsonValue::Bool),
+        arb_number().prop_map(JsonValue::Number),
+        any::<String>().prop_map(JsonValue::String),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(JsonValue::Array),
+            prop::collection::vec((any::<String>(), inner), 0..8).prop_map(JsonValue::Object),
+        ]
+    })
+}
+
+fn reparse(text: &str) -> JsonValue {
+    let options = ParseOptions {
+        allowed_roots: [
+            JsonRootType::Object,
+            JsonRootType::Array,
+            JsonRootType::String,
+            JsonRootType::Number,
+            JsonRootType::Boolean,
+            JsonRootType::Null,
+        ]
+        .into(),
+        ..ParseOptions::default()
+    };
+    JsonDocument::parse_with_options(text, &options)
+        .and_then(|doc| doc.to_value_with_options(&options))
+        .unwrap_or_else(|e| panic!("Failed to reparse {}: {}", text, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        // Test that serializing and reparsing any value yields an equal value
+        #[test]
+        fn test_compact_roundtrip(value in arb_value()) {
+            let text = value.to_string();
+            prop_assert_eq!(reparse(&text), value);
+        }
+
+        // Test that serialization is stable across a roundtrip
+        #[test]
+        fn test_compact_output_is_stable(value in arb_value()) {
+            let text = value.to_string();
+            prop_assert_eq!(reparse(&text).to_string(), text);
+        }
+    }
+}
@@ -1,13 +1,4 @@
-use pest::Parser;
-use pest_derive::Parser;
-use thiserror::Error;
-use json_parser::JsonDocument;
-use anyhow::{Context, Result};
-
-
-#[derive(Parser)]
-#[grammar = "json.pest"]
-pub struct JsonParser;
+use json_parser::{JsonParser, Rule};
 
 #[cfg(test)]
 mod tests {
@@ -129,6 +120,7 @@ mod tests {
             r#""hello world""#,    // Simple string
             r#""with \"quotes\"""#, // Escaped quotes
             r#""unicode: \u00A9""#, // Unicode escape
+            r#""a\/b""#,           // Escaped forward slash
         ];
 
         for s in valid_strings {
@@ -150,6 +142,30 @@ mod tests {
         }
     }
 
+    // Test that unicode escapes take exactly four hex digits of either case
+    #[test]
+    fn test_unicode_escape_hex_digits() {
+        let valid = vec![
+            r#""\u00ff""#,   // Lowercase hex
+            r#""\u00FF""#,   // Uppercase hex
+            r#""\uAbCd""#,   // Mixed case hex
+            r#""\u12345""#,  // Four digits followed by a plain character
+        ];
+        let invalid = vec![
+            r#""\u12""#,     // Too few digits
+            r#""\u""#,       // No digits
+            r#""\u123G""#,   // Non-hex digit
+            r#""\U1234""#,   // Uppercase escape letter
+        ];
+
+        for s in valid {
+            assert!(JsonParser::parse(Rule::string, s).is_ok(), "Failed to parse: {}", s);
+        }
+        for s in invalid {
+            assert!(JsonParser::parse(Rule::string, s).is_err(), "Should fail parsing: {}", s);
+        }
+    }
+
     // Test parsing valid JSON numbers
     #[test]
     fn test_valid_numbers() {
@@ -190,4 +206,56 @@ mod tests {
             assert!(JsonParser::parse(Rule::json, doc).is_err(), "Should fail parsing invalid document: {}", doc);
         }
     }
-}
\ No newline at end of file
+
+    // Test that raw control characters are rejected inside strings but escaped forms are accepted
+    #[test]
+    fn test_control_characters_in_strings() {
+        let raw = vec!["\"a\x01b\"", "\"tab\there\"", "\"line\nbreak\"", "\"\x00\""];
+        for s in raw {
+            assert!(JsonParser::parse(Rule::string, s).is_err(), "Should reject raw control character: {:?}", s);
+        }
+
+        let escaped = vec![r#""\u0001""#, r#""tab\there""#, r#""line\nbreak""#, r#""\u001F""#];
+        for s in escaped {
+            assert!(JsonParser::parse(Rule::string, s).is_ok(), "Failed to parse: {}", s);
+        }
+
+        assert!(JsonParser::parse(Rule::json, "[\"\x01\"]").is_err());
+        assert!(JsonParser::parse(Rule::json, "[\"\u{7f}\"]").is_ok());
+    }
+
+    // Test reading and parsing a fixture file
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_parse_file_fixture() {
+        use json_parser::{JsonDocument, JsonParseError, JsonRootType};
+
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+        let doc = JsonDocument::parse_file(fixtures.join("config.json")).unwrap();
+        assert_eq!(doc.root_type, JsonRootType::Object);
+        assert!(doc.content.starts_with('{'), "Content should be trimmed: {}", doc.content);
+
+        let missing = JsonDocument::parse_file(fixtures.join("missing.json"));
+        assert!(matches!(missing, Err(JsonParseError::Io { ref path, .. }) if path.ends_with("missing.json")));
+
+        let utf16 = JsonDocument::parse_file(fixtures.join("utf16le.json"));
+        assert!(matches!(utf16, Err(JsonParseError::UnsupportedEncoding { ref detected }) if detected == "UTF-16LE"));
+    }
+
+    // Test reading and parsing a fixture file asynchronously
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_parse_file_async_fixture() {
+        use json_parser::{JsonDocument, JsonParseError, JsonRootType};
+
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+        let doc = JsonDocument::parse_file_async(fixtures.join("config.json")).await.unwrap();
+        assert_eq!(doc.root_type, JsonRootType::Object);
+        assert_eq!(doc.content, JsonDocument::parse_file(fixtures.join("config.json")).unwrap().content);
+
+        let missing = JsonDocument::parse_file_async(fixtures.join("missing.json")).await;
+        assert!(matches!(missing, Err(JsonParseError::Io { ref path, .. }) if path.ends_with("missing.json")));
+    }
+}